@@ -0,0 +1,21 @@
+//! Times perft on `TRICKY_POSITION`, which has every kind of move in it. Run with
+//! `cargo bench --bench perft`.
+
+use chess_backend::{Board, TRICKY_POSITION};
+use std::time::{Duration, Instant};
+
+const DEPTH: u32 = 3;
+const RUNS: u32 = 5;
+
+fn main() {
+    chess_backend::init();
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    let mut best = Duration::MAX;
+    let mut nodes = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        nodes = board.perft(DEPTH).nodes;
+        best = best.min(start.elapsed());
+    }
+    println!("perft({DEPTH}) of TRICKY_POSITION: {nodes} nodes, best of {RUNS} runs {best:?}");
+}
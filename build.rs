@@ -2,10 +2,15 @@ extern crate bindgen;
 extern crate cc;
 
 use std::env;
+use std::fs;
 use std::path::PathBuf;
 
+#[path = "build/magics.rs"]
+mod magics;
+
 fn main() {
     println!("cargo:rerun-if-changed=c_lib/*");
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
 
     cc::Build::new()
         .file("c_lib/bitboard.c")
@@ -20,16 +25,27 @@ fn main() {
     cc::Build::new()
         .file("c_lib/targets/pawn.c")
         .compile("pawn");
-    cc::Build::new()
-        .file("c_lib/targets/sliders.c")
-        .compile("sliders");
+
+    // the PEXT tables are only used on x86-64, when the CPU turns out to support BMI2
+    let pext = env::var_os("CARGO_FEATURE_PEXT").is_some()
+        && env::var("CARGO_CFG_TARGET_ARCH").unwrap() == "x86_64";
+    fs::write(out_path.join("slider_tables.h"), magics::generate(pext))
+        .expect("Couldn't write slider tables!");
+    let mut sliders = cc::Build::new();
+    sliders.file("c_lib/targets/sliders.c").include(&out_path);
+    if pext {
+        sliders.define("USE_PEXT", None);
+    }
+    sliders.compile("sliders");
 
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
+        .derive_partialeq(true)
+        .derive_eq(true)
+        .derive_hash(true)
         .generate()
         .expect("Unable to generate bindings");
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
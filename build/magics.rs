@@ -0,0 +1,198 @@
+//! Generates the attack tables of the sliding pieces for `c_lib/targets/sliders.c`, so that they
+//! are static data instead of being filled in at startup.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy)]
+enum Slider {
+    Rook,
+    Bishop,
+}
+impl Slider {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rook => "rook",
+            Self::Bishop => "bishop",
+        }
+    }
+
+    fn directions(self) -> [(i32, i32); 4] {
+        match self {
+            Self::Rook => [(1, 0), (-1, 0), (0, 1), (0, -1)],
+            Self::Bishop => [(1, 1), (1, -1), (-1, 1), (-1, -1)],
+        }
+    }
+
+    /// The squares attacked from `square`, up to and including the first blocker of each ray.
+    fn attacks(self, square: i32, blockers: u64) -> u64 {
+        let mut res = 0;
+        for (file_step, rank_step) in self.directions() {
+            let (mut file, mut rank) = (square % 8 + file_step, square / 8 + rank_step);
+            while (0..8).contains(&file) && (0..8).contains(&rank) {
+                let bit = 1 << (rank * 8 + file);
+                res |= bit;
+                if blockers & bit != 0 {
+                    break;
+                }
+                file += file_step;
+                rank += rank_step;
+            }
+        }
+        res
+    }
+
+    /// The squares whose occupancy changes the attacks from `square`. The last square of a ray
+    /// never blocks anything.
+    fn mask(self, square: i32) -> u64 {
+        let mut res = 0;
+        for (file_step, rank_step) in self.directions() {
+            let (mut file, mut rank) = (square % 8 + file_step, square / 8 + rank_step);
+            while (0..8).contains(&(file + file_step)) && (0..8).contains(&(rank + rank_step)) {
+                res |= 1 << (rank * 8 + file);
+                file += file_step;
+                rank += rank_step;
+            }
+        }
+        res
+    }
+}
+
+/// xorshift64, seeded with a constant so that every build produces the same tables.
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Magics with few bits set are found much sooner.
+    fn sparse(&mut self) -> u64 {
+        self.next() & self.next() & self.next()
+    }
+}
+
+/// Every subset of `mask`.
+fn subsets(mask: u64) -> Vec<u64> {
+    let mut res = Vec::with_capacity(1 << mask.count_ones());
+    let mut subset = 0u64;
+    loop {
+        res.push(subset);
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            return res;
+        }
+    }
+}
+
+/// Gathers the bits of `value` selected by `mask` into the low bits, like the BMI2 instruction.
+fn pext(value: u64, mut mask: u64) -> u64 {
+    let mut res = 0;
+    let mut bit = 0;
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            res |= 1 << bit;
+        }
+        bit += 1;
+        mask &= mask - 1;
+    }
+    res
+}
+
+/// Searches for a multiplier that maps every occupancy to an index of `bits` bits, without two
+/// occupancies with different attacks sharing an index.
+fn find_magic(occupancies: &[u64], attacks: &[u64], mask: u64, rng: &mut Rng) -> u64 {
+    let bits = mask.count_ones();
+    let mut table = vec![0u64; 1 << bits];
+    let mut used = vec![0u32; 1 << bits];
+    for attempt in 1.. {
+        let magic = rng.sparse();
+        if (mask.wrapping_mul(magic) >> 56).count_ones() < 6 {
+            continue;
+        }
+        // `used` holds the attempt that last wrote an index, which saves clearing it
+        let fits = occupancies
+            .iter()
+            .zip(attacks)
+            .all(|(&occupancy, &attack)| {
+                let index = (occupancy.wrapping_mul(magic) >> (64 - bits)) as usize;
+                if used[index] != attempt {
+                    used[index] = attempt;
+                    table[index] = attack;
+                    true
+                } else {
+                    table[index] == attack
+                }
+            });
+        if fits {
+            return magic;
+        }
+    }
+    unreachable!()
+}
+
+fn write_array<T: std::fmt::Display>(out: &mut String, declaration: &str, values: &[T]) {
+    writeln!(out, "static const {declaration}[{}] = {{", values.len()).unwrap();
+    for row in values.chunks(4) {
+        let row: Vec<_> = row.iter().map(|value| format!("{value}")).collect();
+        writeln!(out, "    {},", row.join(", ")).unwrap();
+    }
+    out.push_str("};\n\n");
+}
+
+/// Writes the masks, magics, shifts and offsets of every square and the attacks they index for
+/// both sliders, as a C header. With `pext`, the attacks are also written in the order of the
+/// indices `_pext_u64` computes from the mask.
+pub fn generate(pext_tables: bool) -> String {
+    let mut rng = Rng(0x9E3779B97F4A7C15);
+    let mut out = String::from("// generated by build.rs, do not edit\n\n");
+    for slider in [Slider::Rook, Slider::Bishop] {
+        let name = slider.name();
+        let mut masks = Vec::new();
+        let mut magics = Vec::new();
+        let mut shifts = Vec::new();
+        let mut offsets = Vec::new();
+        let mut table = Vec::new();
+        let mut pext_table = Vec::new();
+        for square in 0..64 {
+            let mask = slider.mask(square);
+            let bits = mask.count_ones();
+            let occupancies = subsets(mask);
+            let attacks: Vec<u64> = occupancies
+                .iter()
+                .map(|&occupancy| slider.attacks(square, occupancy))
+                .collect();
+            let magic = find_magic(&occupancies, &attacks, mask, &mut rng);
+
+            let offset = table.len();
+            table.resize(offset + (1 << bits), 0);
+            pext_table.resize(offset + (1 << bits), 0);
+            for (&occupancy, &attack) in occupancies.iter().zip(&attacks) {
+                table[offset + (occupancy.wrapping_mul(magic) >> (64 - bits)) as usize] = attack;
+                pext_table[offset + pext(occupancy, mask) as usize] = attack;
+            }
+            masks.push(format!("{mask:#018x}ULL"));
+            magics.push(format!("{magic:#018x}ULL"));
+            shifts.push(64 - bits);
+            offsets.push(offset);
+        }
+
+        let hex = |values: &[u64]| -> Vec<String> {
+            values.iter().map(|v| format!("{v:#018x}ULL")).collect()
+        };
+        write_array(&mut out, &format!("U64 {name}_masks"), &masks);
+        write_array(&mut out, &format!("U64 {name}_magics"), &magics);
+        write_array(&mut out, &format!("int {name}_shifts"), &shifts);
+        write_array(&mut out, &format!("unsigned {name}_offsets"), &offsets);
+        write_array(&mut out, &format!("U64 {name}_attacks"), &hex(&table));
+        if pext_tables {
+            write_array(
+                &mut out,
+                &format!("U64 {name}_pext_attacks"),
+                &hex(&pext_table),
+            );
+        }
+    }
+    out
+}
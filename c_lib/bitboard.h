@@ -34,6 +34,7 @@ typedef struct square_array {
   int squares[];
 } square_array;
 
+// The result is allocated with malloc and must be freed by the caller.
 square_array * extract_squares(U64 bitboard);
 bitboard_base createBase(piece_map_bitboards white, piece_map_bitboards black);
 U64 squares_to_bitboard(int squares[], int size);
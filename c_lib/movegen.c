@@ -8,7 +8,6 @@ void init_targets() {
   init_king_attacks();
   init_knight_attacks();
   init_pawn_attacks();
-  init_sliders();
 }
 
 U64 third_rank = 16711680ULL;
@@ -65,41 +64,41 @@ U64 queenTargets(int square, U64 occupancy) {
          get_bishop_attacks(square, occupancy);
 }
 
+// index of the least significant set bit, which is then cleared
+static int pop_ls1b(U64 *bitboard) {
+  int square = __builtin_ctzll(*bitboard);
+  *bitboard &= *bitboard - 1;
+  return square;
+}
+
+// Iterates over the bits of the piece bitboards in place, so that no memory is allocated.
 U64 generateAttackTargets(piece_map_bitboards pieces, int colour,
                           U64 occupancy) {
   U64 res = 0;
 
-  square_array *king_arr = extract_squares(pieces.king);
-  square_array *queen_arr = extract_squares(pieces.queens);
-  square_array *bishop_arr = extract_squares(pieces.bishops);
-  square_array *knight_arr = extract_squares(pieces.knights);
-  square_array *rook_arr = extract_squares(pieces.rooks);
-  square_array *pawn_arr = extract_squares(pieces.pawns);
-  int i;
-
   // king targets
-  for (i = 0; i < king_arr->size; i++) {
-    res |= kingTargets(king_arr->squares[i]);
+  while (pieces.king) {
+    res |= kingTargets(pop_ls1b(&pieces.king));
   }
   // queen targets
-  for (i = 0; i < queen_arr->size; i++) {
-    res |= queenTargets(queen_arr->squares[i], occupancy);
+  while (pieces.queens) {
+    res |= queenTargets(pop_ls1b(&pieces.queens), occupancy);
   }
   // bishop targets
-  for (i = 0; i < bishop_arr->size; i++) {
-    res |= bishopTargets(bishop_arr->squares[i], occupancy);
+  while (pieces.bishops) {
+    res |= bishopTargets(pop_ls1b(&pieces.bishops), occupancy);
   }
   // knight targets
-  for (i = 0; i < knight_arr->size; i++) {
-    res |= knightTargets(knight_arr->squares[i]);
+  while (pieces.knights) {
+    res |= knightTargets(pop_ls1b(&pieces.knights));
   }
   // rook targets
-  for (i = 0; i < rook_arr->size; i++) {
-    res |= rookTargets(rook_arr->squares[i], occupancy);
+  while (pieces.rooks) {
+    res |= rookTargets(pop_ls1b(&pieces.rooks), occupancy);
   }
   // pawn targets
-  for (i = 0; i < pawn_arr->size; i++) {
-    res |= pawnAttackTargets(pawn_arr->squares[i], colour);
+  while (pieces.pawns) {
+    res |= pawnAttackTargets(pop_ls1b(&pieces.pawns), colour);
   }
 
   return res;
@@ -1,5 +1,4 @@
 #include "king.h"
-#include <stdio.h>
 /***************************************\
                 Credit to
             Code Moneky King
@@ -11,8 +10,8 @@
 // bit manipulation macros
 #define set_bit(bitboard, index) (bitboard |= (1ULL << index))
 
-U64 not_a_file = 18374403900871474942ULL;
-U64 not_h_file = 9187201950435737471ULL;
+static U64 not_a_file = 18374403900871474942ULL;
+static U64 not_h_file = 9187201950435737471ULL;
 // king attacks array [square]
 U64 king_attacks[64];
 
@@ -51,7 +50,6 @@ U64 mask_king_attacks(int square) {
 
 // init pre-calculated attack tables for leaper pieces (pawns, knights, kings)
 void init_king_attacks() {
-  printf("Generating King Targets\n");
   // loop over 64 board squares
   for (int square = 0; square < 64; square++) {
     king_attacks[square] = mask_king_attacks(square);
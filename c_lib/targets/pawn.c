@@ -4,17 +4,14 @@
             Code Moneky King
 \**************************************/
 
-// headers
-#include <stdio.h>
-
 // bit manipulation macros
 #define set_bit(bitboard, index) (bitboard |= (1ULL << index))
 
 // define sides
 enum { white, black };
 // not A file bitboard
-U64 not_a_file = 18374403900871474942ULL;
-U64 not_h_file = 9187201950435737471ULL;
+static U64 not_a_file = 18374403900871474942ULL;
+static U64 not_h_file = 9187201950435737471ULL;
 
 // pawn atacks array [side][bitboard]
 // Shouldn't actually need first or eighth rank, but it's more of a hassle
@@ -77,7 +74,6 @@ U64 mask_pawn_targets(int side, int square) {
 
 // init pre-calculated attack tables for leaper pieces (pawns, knights, kings)
 void init_pawn_attacks() {
-  printf("Generating Pawn Attack Targets\n");
   // loop over 64 board squares
   for (int square = 0; square < 64; square++) {
     // init pawn attacks
@@ -1,4 +1,3 @@
 #define U64 unsigned long long
-void init_sliders();
 U64 get_bishop_attacks(int square, U64 occupancy);
 U64 get_rook_attacks(int square, U64 occupancy);
@@ -0,0 +1,221 @@
+use crate::square::Square;
+use std::{
+    fmt::Display,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
+
+/// A set of squares, with a bit for every square in the order of `Square`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+/// A direction on the board, as seen by white.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+        Self::NorthEast,
+        Self::NorthWest,
+        Self::SouthEast,
+        Self::SouthWest,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+enum Line {
+    File,
+    Rank,
+    Diagonal,
+    AntiDiagonal,
+}
+
+/// Splits the board into the files, ranks or diagonals of one direction.
+const fn lines<const N: usize>(kind: Line) -> [Bitboard; N] {
+    let mut res = [Bitboard(0); N];
+    let mut square = 0;
+    while square < 64 {
+        let (file, rank) = (square % 8, square / 8);
+        let line = match kind {
+            Line::File => file,
+            Line::Rank => rank,
+            Line::Diagonal => rank + 7 - file,
+            Line::AntiDiagonal => file + rank,
+        };
+        res[line].0 |= 1 << square;
+        square += 1;
+    }
+    res
+}
+
+impl Bitboard {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(!0);
+
+    pub const FILE_A: Self = Self(0x0101010101010101);
+    pub const FILE_H: Self = Self(Self::FILE_A.0 << 7);
+    pub const RANK_1: Self = Self(0xFF);
+    pub const RANK_8: Self = Self(Self::RANK_1.0 << 56);
+    /// The files from a to h.
+    pub const FILES: [Self; 8] = lines(Line::File);
+    /// The ranks from 1 to 8.
+    pub const RANKS: [Self; 8] = lines(Line::Rank);
+    /// The diagonals running towards h8, from the one through h1 to the one through a8.
+    pub const DIAGONALS: [Self; 15] = lines(Line::Diagonal);
+    /// The diagonals running towards a8, from the one through a1 to the one through h8.
+    pub const ANTI_DIAGONALS: [Self; 15] = lines(Line::AntiDiagonal);
+    pub const LIGHT_SQUARES: Self = Self(0x55AA55AA55AA55AA);
+    pub const DARK_SQUARES: Self = Self(!Self::LIGHT_SQUARES.0);
+
+    /// The number of squares in the set.
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, square: Square) -> bool {
+        self.0 & 1 << square as u8 != 0
+    }
+
+    /// The square with the lowest index in the set.
+    pub fn first(self) -> Option<Square> {
+        Square::from_index(self.0.trailing_zeros() as usize)
+    }
+
+    /// Moves every square one step in `direction`. Squares that would leave the board are
+    /// dropped instead of wrapping around to the other side.
+    pub fn shift(self, direction: Direction) -> Self {
+        let not_a = self.0 & !Self::FILE_A.0;
+        let not_h = self.0 & !Self::FILE_H.0;
+        Self(match direction {
+            Direction::North => self.0 << 8,
+            Direction::South => self.0 >> 8,
+            Direction::East => not_h << 1,
+            Direction::West => not_a >> 1,
+            Direction::NorthEast => not_h << 9,
+            Direction::NorthWest => not_a << 7,
+            Direction::SouthEast => not_h >> 7,
+            Direction::SouthWest => not_a >> 9,
+        })
+    }
+
+    /// The squares of the set, from the lowest index to the highest.
+    pub fn iter(self) -> Squares {
+        Squares(self.0)
+    }
+}
+
+/// Prints the board from white's side, with a mark on every square in the set.
+impl Display for Bitboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut bits: Vec<char> = format!("{:#0b}", self.0)[2..].chars().collect();
+        for _ in 0..(64 - bits.len()) {
+            bits.insert(0, '0');
+        }
+        let mut ranks: Vec<Vec<char>> = bits.chunks(8).map(|s| s.into()).collect();
+        for rank in &mut ranks {
+            rank.reverse();
+            for square in rank {
+                if *square == '0' {
+                    write!(f, ". ")?;
+                } else {
+                    write!(f, "{square} ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        square.bitboard()
+    }
+}
+
+impl From<u64> for Bitboard {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+macro_rules! bit_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident) => {
+        impl<T: Into<Bitboard>> $op<T> for Bitboard {
+            type Output = Self;
+
+            fn $method(self, rhs: T) -> Self {
+                Self(self.0.$method(rhs.into().0))
+            }
+        }
+        impl<T: Into<Bitboard>> $assign<T> for Bitboard {
+            fn $assign_method(&mut self, rhs: T) {
+                self.0.$assign_method(rhs.into().0);
+            }
+        }
+    };
+}
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+/// The squares of a bitboard, popped from the lowest index up.
+#[derive(Debug, Clone)]
+pub struct Squares(u64);
+impl Iterator for Squares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        let square = Square::from_index(self.0.trailing_zeros() as usize)?;
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+impl ExactSizeIterator for Squares {}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Squares {
+        self.iter()
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Square>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::EMPTY, |res, square| res | square)
+    }
+}
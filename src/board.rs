@@ -1,24 +1,49 @@
 use crate::{
-    bishopTargets, bitboard_base, castling_rights, createBase, generateAttackTargets, kingTargets,
-    knightTargets, pawnAttackTargets, pawnTargets, piece_map_bitboards, queenTargets, rookTargets,
+    bishopTargets, bitboard_base, castling_rights, createBase, generateAttackTargets,
+    geometry::tables,
+    kingTargets, knightTargets, pawnAttackTargets, pawnTargets, piece_map_bitboards, queenTargets,
+    rookTargets,
     utils::{extract_squares, squares::*, Colour, Piece, Pieces},
-    START_POSITION,
+    zobrist, Bitboard, Square, START_POSITION,
 };
 use core::panic;
 use std::fmt::Display;
 
-type TargetFunction = Box<dyn Fn(i32) -> u64>;
-type MutateFunction = Box<dyn Fn(&mut Board)>;
+// castling rights as bits, in the order of the fields of `castling_rights`
+const WHITE_KING: u8 = 1;
+const WHITE_QUEEN: u8 = 2;
+const BLACK_KING: u8 = 4;
+const BLACK_QUEEN: u8 = 8;
+
+/// The castling rights kept by a move that starts or ends on a square. Moving a king or rook
+/// away from home, or capturing a rook there, loses the rights that depend on it.
+const CASTLING_MASKS: [u8; 64] = {
+    let mut masks = [WHITE_KING | WHITE_QUEEN | BLACK_KING | BLACK_QUEEN; 64];
+    masks[a1 as usize] &= !WHITE_QUEEN;
+    masks[h1 as usize] &= !WHITE_KING;
+    masks[e1 as usize] &= !(WHITE_KING | WHITE_QUEEN);
+    masks[a8 as usize] &= !BLACK_QUEEN;
+    masks[h8 as usize] &= !BLACK_KING;
+    masks[e8 as usize] &= !(BLACK_KING | BLACK_QUEEN);
+    masks
+};
+
+/// Number of halfmoves without a pawn move or capture after which a draw may be claimed.
+pub const HALFMOVE_CLAIM_LIMIT: i32 = 100;
+/// Number of halfmoves without a pawn move or capture after which the game is drawn.
+pub const HALFMOVE_LIMIT: i32 = 150;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Side {
+/// The side of the board a castling move is made to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
     King,
     Queen,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum MoveType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MoveType {
     Standard,
+    DoublePawnPush,
     Castling(Side),
     EnPassent,
     Promotion(Piece),
@@ -32,24 +57,25 @@ pub enum GameState {
     Finished(FinishedState),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FinishedState {
     Win(Colour, ReasonWin),
     Draw(ReasonDraw),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReasonWin {
     Checkmate,
     Resignation,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReasonDraw {
     Stalemate,
     InsufficientMaterial,
     HalfmoveLimit,
     Agreement,
+    Repetition,
 }
 
 impl bitboard_base {
@@ -101,7 +127,7 @@ impl bitboard_base {
             *side_bitboard ^= starting_sqaure | destination_square;
         }
 
-        for other_bitboard in vec![
+        for other_bitboard in [
             &mut other.pawns,
             &mut other.king,
             &mut other.queens,
@@ -116,15 +142,11 @@ impl bitboard_base {
         if colour == Colour::White {
             let white = side;
             let black = other;
-            unsafe {
-                return createBase(white, black);
-            }
+            unsafe { createBase(white, black) }
         } else {
             let white = other;
             let black = side;
-            unsafe {
-                return createBase(white, black);
-            }
+            unsafe { createBase(white, black) }
         }
     }
 
@@ -133,91 +155,51 @@ impl bitboard_base {
         colour: Colour,
         piece: Piece,
         bitboard: u64,
-        target_function: TargetFunction,
-    ) -> Vec<(ChessMoveBase, Option<MutateFunction>)> {
-        let mut res = Vec::new();
+        occupancy: u64,
+        res: &mut Vec<ChessMoveBase>,
+    ) {
+        let side_occupied = self.get_side_occupied(colour);
         for square in extract_squares(bitboard) {
-            let side_occupied = self.get_side_occupied(colour);
-            for target in
-                extract_squares((target_function(square) ^ side_occupied) & !side_occupied)
-            {
-                let res_board = self.make_move(piece, 1 << square, 1 << target, colour, None);
-                let f =
-                    if piece == Piece::Pawn(Colour::White) && target - square == 16 {
-                        Some(Box::new(move |b: &mut Board| b.killer_square = square + 8)
-                            as MutateFunction)
-                    } else if piece == Piece::Pawn(Colour::Black) && square - target == 16 {
-                        Some(Box::new(move |b: &mut Board| b.killer_square = square - 8)
-                            as MutateFunction)
-                    } else if let Piece::King(c) = piece {
-                        match c {
-                            Colour::White => Some(Box::new(|b: &mut Board| {
-                                b.castling_rights.white_king = false;
-                                b.castling_rights.white_queen = false;
-                            }) as MutateFunction),
-                            Colour::Black => Some(Box::new(|b: &mut Board| {
-                                b.castling_rights.black_king = false;
-                                b.castling_rights.black_queen = false;
-                            }) as MutateFunction),
-                        }
-                    } else if let Piece::Rook(c) = piece {
-                        match c {
-                            Colour::White => {
-                                if square == a1 {
-                                    Some(Box::new(|b: &mut Board| {
-                                        b.castling_rights.white_queen = false
-                                    }) as MutateFunction)
-                                } else if square == h1 {
-                                    Some(Box::new(|b: &mut Board| {
-                                        b.castling_rights.white_king = false
-                                    }) as MutateFunction)
-                                } else {
-                                    None
-                                }
-                            }
-                            Colour::Black => {
-                                if square == a8 {
-                                    Some(Box::new(|b: &mut Board| {
-                                        b.castling_rights.black_queen = false
-                                    }) as MutateFunction)
-                                } else if square == h8 {
-                                    Some(Box::new(|b: &mut Board| {
-                                        b.castling_rights.black_king = false
-                                    }) as MutateFunction)
-                                } else {
-                                    None
-                                }
-                            }
-                        }
-                    } else {
-                        None
-                    };
-                res.push((
-                    ChessMoveBase {
-                        starting_sqaure: Some(square),
-                        destination_square: Some(target),
-                        piece,
-                        move_type: MoveType::Standard,
-                        colour,
-                        res_board,
-                    },
-                    f,
-                ))
+            let targets = unsafe {
+                match piece {
+                    Piece::Pawn(_) => pawnTargets(square, colour.as_int(), occupancy),
+                    Piece::King(_) => kingTargets(square),
+                    Piece::Queen(_) => queenTargets(square, occupancy),
+                    Piece::Bishop(_) => bishopTargets(square, occupancy),
+                    Piece::Knight(_) => knightTargets(square),
+                    Piece::Rook(_) => rookTargets(square, occupancy),
+                }
+            };
+            for target in extract_squares(targets & !side_occupied) {
+                let move_type = if matches!(piece, Piece::Pawn(_)) && (target - square).abs() == 16
+                {
+                    MoveType::DoublePawnPush
+                } else {
+                    MoveType::Standard
+                };
+                res.push(ChessMoveBase {
+                    starting_sqaure: square,
+                    destination_square: target,
+                    piece,
+                    move_type,
+                    colour,
+                    res_board: self.make_move(piece, 1 << square, 1 << target, colour, None),
+                })
             }
         }
-        res
     }
 
-    fn get_pseudo_legal_castling(
-        &self,
-        colour: Colour,
-    ) -> Vec<(ChessMoveBase, Option<MutateFunction>)> {
-        let (king_side, queen_side, mutate_king, mutate_queen): (
-            bitboard_base,
-            bitboard_base,
-            MutateFunction,
-            MutateFunction,
-        ) = match colour {
+    fn get_pseudo_legal_castling(&self, colour: Colour, res: &mut Vec<ChessMoveBase>) {
+        // the king moves two squares towards the rook it castles with, from its starting square
+        let king_square = match colour {
+            Colour::White => e1,
+            Colour::Black => e8,
+        };
+        if self.get_side(colour).king & 1 << king_square == 0 {
+            return;
+        }
+
+        let (king_side, queen_side) = match colour {
             Colour::White => {
                 let mut king_side = self.white;
                 let mut queen_side = self.white;
@@ -231,8 +213,6 @@ impl bitboard_base {
                     (
                         createBase(king_side, self.black),
                         createBase(queen_side, self.black),
-                        Box::new(|b: &mut Board| b.castling_rights.white_king = false),
-                        Box::new(|b: &mut Board| b.castling_rights.white_queen = false),
                     )
                 }
             }
@@ -249,37 +229,27 @@ impl bitboard_base {
                     (
                         createBase(self.white, king_side),
                         createBase(self.white, queen_side),
-                        Box::new(|b: &mut Board| b.castling_rights.black_king = false),
-                        Box::new(|b: &mut Board| b.castling_rights.black_queen = false),
                     )
                 }
             }
         };
 
-        vec![
-            (
-                ChessMoveBase {
-                    starting_sqaure: None,
-                    destination_square: None,
-                    piece: Piece::King(colour),
-                    move_type: MoveType::Castling(Side::King),
-                    colour,
-                    res_board: king_side,
-                },
-                Some(mutate_king),
-            ),
-            (
-                ChessMoveBase {
-                    starting_sqaure: None,
-                    destination_square: None,
-                    piece: Piece::King(colour),
-                    move_type: MoveType::Castling(Side::Queen),
-                    colour,
-                    res_board: queen_side,
-                },
-                Some(mutate_queen),
-            ),
-        ]
+        res.push(ChessMoveBase {
+            starting_sqaure: king_square,
+            destination_square: king_square + 2,
+            piece: Piece::King(colour),
+            move_type: MoveType::Castling(Side::King),
+            colour,
+            res_board: king_side,
+        });
+        res.push(ChessMoveBase {
+            starting_sqaure: king_square,
+            destination_square: king_square - 2,
+            piece: Piece::King(colour),
+            move_type: MoveType::Castling(Side::Queen),
+            colour,
+            res_board: queen_side,
+        });
     }
 
     fn get_enpassent_move(
@@ -287,7 +257,7 @@ impl bitboard_base {
         start_square: i32,
         killer_square: i32,
         colour: Colour,
-    ) -> (ChessMoveBase, Option<MutateFunction>) {
+    ) -> ChessMoveBase {
         let mut white = self.white;
         let mut black = self.black;
         match colour {
@@ -302,17 +272,14 @@ impl bitboard_base {
         };
         unsafe {
             let res_board = createBase(white, black);
-            (
-                ChessMoveBase {
-                    starting_sqaure: Some(start_square),
-                    destination_square: Some(killer_square),
-                    piece: Piece::Pawn(colour),
-                    move_type: MoveType::EnPassent,
-                    colour,
-                    res_board,
-                },
-                None,
-            )
+            ChessMoveBase {
+                starting_sqaure: start_square,
+                destination_square: killer_square,
+                piece: Piece::Pawn(colour),
+                move_type: MoveType::EnPassent,
+                colour,
+                res_board,
+            }
         }
     }
 
@@ -322,13 +289,14 @@ impl bitboard_base {
         colour: Colour,
         pawns: u64,
         occupancy: u64,
-        res: &mut Vec<(ChessMoveBase, Option<MutateFunction>)>,
+        res: &mut Vec<ChessMoveBase>,
     ) {
+        let side_occupied = self.get_side_occupied(colour);
         for starting_sqaure in extract_squares(pawns) {
             unsafe {
-                for target in
-                    extract_squares(pawnTargets(starting_sqaure, colour.as_int(), occupancy))
-                {
+                for target in extract_squares(
+                    pawnTargets(starting_sqaure, colour.as_int(), occupancy) & !side_occupied,
+                ) {
                     if 1u64 << target & back_rank != 0 {
                         for p in [
                             Piece::Queen(colour),
@@ -336,23 +304,20 @@ impl bitboard_base {
                             Piece::Knight(colour),
                             Piece::Bishop(colour),
                         ] {
-                            res.push((
-                                ChessMoveBase {
-                                    starting_sqaure: Some(starting_sqaure),
-                                    destination_square: Some(target),
-                                    piece: Piece::Pawn(colour),
-                                    move_type: MoveType::Promotion(p),
+                            res.push(ChessMoveBase {
+                                starting_sqaure,
+                                destination_square: target,
+                                piece: Piece::Pawn(colour),
+                                move_type: MoveType::Promotion(p),
+                                colour,
+                                res_board: self.make_move(
+                                    Piece::Pawn(colour),
+                                    1u64 << starting_sqaure,
+                                    1u64 << target,
                                     colour,
-                                    res_board: self.make_move(
-                                        Piece::Pawn(colour),
-                                        1u64 << starting_sqaure,
-                                        1u64 << target,
-                                        colour,
-                                        Some(p),
-                                    ),
-                                },
-                                None,
-                            ))
+                                    Some(p),
+                                ),
+                            })
                         }
                     }
                 }
@@ -365,36 +330,38 @@ impl bitboard_base {
         colour: Colour,
         pawns: u64,
         occupancy: u64,
-    ) -> Vec<(ChessMoveBase, Option<MutateFunction>)> {
-        let mut res = Vec::new();
-        if colour == Colour::White && pawns & 71776119061217280 != 0 {
-            self.add_promotion_moves(18374686479671623680, colour, pawns, occupancy, &mut res);
-        } else if colour == Colour::Black && pawns & 65280 != 0 {
-            self.add_promotion_moves(255, colour, pawns, occupancy, &mut res);
+        res: &mut Vec<ChessMoveBase>,
+    ) {
+        let ranks = Bitboard::RANKS.map(|rank| rank.0);
+        if colour == Colour::White && pawns & ranks[6] != 0 {
+            self.add_promotion_moves(ranks[7], colour, pawns, occupancy, res);
+        } else if colour == Colour::Black && pawns & ranks[1] != 0 {
+            self.add_promotion_moves(ranks[0], colour, pawns, occupancy, res);
         }
-        res
     }
 
-    pub fn get_pseudo_legal_moves(
+    pub(crate) fn get_pseudo_legal_moves(
         &self,
         colour: Colour,
         killer_square: i32,
-    ) -> Vec<(ChessMoveBase, Option<MutateFunction>)> {
-        let occupancy = self.white_occupied + self.black_occupied;
-        let mut res = Vec::new();
+    ) -> Vec<ChessMoveBase> {
+        let occupancy = self.white_occupied | self.black_occupied;
+        let side = self.get_side(colour);
+        let mut res = Vec::with_capacity(64);
 
         // --- Pawn Moves ---
-        res.append(&mut self.get_promotion_moves(colour, self.get_side(colour).pawns, occupancy));
-        res.append(&mut self.get_pseudo_legal_piece_moves(
+        self.get_promotion_moves(colour, side.pawns, occupancy, &mut res);
+        self.get_pseudo_legal_piece_moves(
             colour,
             Piece::Pawn(colour),
-            self.get_side(colour).pawns,
-            Self::pawn_target_function(colour, occupancy),
-        ));
+            side.pawns,
+            occupancy,
+            &mut res,
+        );
         // En passent is only handled if the board has a killer square
         if killer_square >= 0 {
             unsafe {
-                for square in extract_squares(self.get_side(colour).pawns) {
+                for square in extract_squares(side.pawns) {
                     if 1 << killer_square as u32 & pawnAttackTargets(square, colour.as_int()) != 0 {
                         res.push(self.get_enpassent_move(square, killer_square, colour));
                     }
@@ -403,76 +370,47 @@ impl bitboard_base {
         }
 
         // --- King Moves ---
-        res.append(&mut self.get_pseudo_legal_piece_moves(
+        self.get_pseudo_legal_piece_moves(
             colour,
             Piece::King(colour),
-            self.get_side(colour).king,
-            Self::king_target_function(),
-        ));
+            side.king,
+            occupancy,
+            &mut res,
+        );
         // castling is always generated as pseudo-legal and might be removed when checking legality
-        res.append(&mut self.get_pseudo_legal_castling(colour));
-
-        // --- Queen Moves ---
-        res.append(&mut self.get_pseudo_legal_piece_moves(
-            colour,
-            Piece::Queen(colour),
-            self.get_side(colour).queens,
-            Self::queen_target_function(occupancy),
-        ));
-
-        // --- Bishop Moves ---
-        res.append(&mut self.get_pseudo_legal_piece_moves(
-            colour,
-            Piece::Bishop(colour),
-            self.get_side(colour).bishops,
-            Self::bishop_target_function(occupancy),
-        ));
-
-        // --- Knight Moves ---
-        res.append(&mut self.get_pseudo_legal_piece_moves(
-            colour,
-            Piece::Knight(colour),
-            self.get_side(colour).knights,
-            Self::knight_target_function(),
-        ));
-
-        // --- Rook Moves ---
-        res.append(&mut self.get_pseudo_legal_piece_moves(
-            colour,
-            Piece::Rook(colour),
-            self.get_side(colour).rooks,
-            Self::rook_target_function(occupancy),
-        ));
+        self.get_pseudo_legal_castling(colour, &mut res);
+
+        // --- Queen, Bishop, Knight and Rook Moves ---
+        for (piece, bitboard) in [
+            (Piece::Queen(colour), side.queens),
+            (Piece::Bishop(colour), side.bishops),
+            (Piece::Knight(colour), side.knights),
+            (Piece::Rook(colour), side.rooks),
+        ] {
+            self.get_pseudo_legal_piece_moves(colour, piece, bitboard, occupancy, &mut res);
+        }
 
         res
     }
+}
 
-    fn pawn_target_function(colour: Colour, occupancy: u64) -> TargetFunction {
-        Box::new(move |s| unsafe { pawnTargets(s, colour.as_int(), occupancy) })
-    }
-    fn king_target_function() -> TargetFunction {
-        Box::new(|s| unsafe { kingTargets(s) })
-    }
-    fn queen_target_function(occupancy: u64) -> TargetFunction {
-        Box::new(move |s| unsafe { queenTargets(s, occupancy) })
-    }
-    fn bishop_target_function(occupancy: u64) -> TargetFunction {
-        Box::new(move |s| unsafe { bishopTargets(s, occupancy) })
-    }
-    fn knight_target_function() -> TargetFunction {
-        Box::new(|s| unsafe { knightTargets(s) })
-    }
-    fn rook_target_function(occupancy: u64) -> TargetFunction {
-        Box::new(move |s| unsafe { rookTargets(s, occupancy) })
+impl castling_rights {
+    /// Takes away the rights lost by a move from `source` to `destination`.
+    pub(crate) fn update(&mut self, source: i32, destination: i32) {
+        let mask = CASTLING_MASKS[source as usize] & CASTLING_MASKS[destination as usize];
+        self.white_king &= mask & WHITE_KING != 0;
+        self.white_queen &= mask & WHITE_QUEEN != 0;
+        self.black_king &= mask & BLACK_KING != 0;
+        self.black_queen &= mask & BLACK_QUEEN != 0;
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct ChessMoveBase {
-    starting_sqaure: Option<i32>,
-    destination_square: Option<i32>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChessMoveBase {
+    starting_sqaure: i32,
+    destination_square: i32,
     piece: Piece,
-    move_type: MoveType,
+    pub(crate) move_type: MoveType,
     colour: Colour,
     res_board: bitboard_base,
 }
@@ -489,44 +427,33 @@ impl ChessMoveBase {
             let other_attacks = generateAttackTargets(
                 other_side,
                 self.colour.other().as_int(),
-                self.res_board.white_occupied + self.res_board.black_occupied,
+                self.res_board.white_occupied | self.res_board.black_occupied,
             );
             // king should not be in check after the move has been made
             if side.king & other_attacks != 0 {
                 return false;
             }
             // pawns should not be on any back rank
-            if side.pawns + other_side.pawns & 18374686479671623935 != 0 {
+            if (side.pawns | other_side.pawns) & (Bitboard::RANK_1 | Bitboard::RANK_8).0 != 0 {
                 return false;
             }
             if let MoveType::Castling(castling_side) = self.move_type {
                 // When castling, the coresponding castling right must be true, there must be no
                 // pieces between the king and rook, the rooks must not have been captured, and the
                 // squares over which the king passes must not be attacked by enemy pieces
-                if !((castling_side == Side::King
-                    && self.colour == Colour::White
-                    && castling_rights.white_king
-                    && (96 & occupancy == 0)
-                    && (128 & rooks.0 != 0)
-                    && (112 & other_attacks == 0))
-                    || (castling_side == Side::Queen
-                        && self.colour == Colour::White
-                        && castling_rights.white_queen
-                        && (14 & occupancy == 0)
-                        && (1 & rooks.0 != 0)
-                        && (30 & other_attacks == 0))
-                    || (castling_side == Side::King
-                        && self.colour == Colour::Black
-                        && castling_rights.black_king
-                        && (6917529027641081856 & occupancy == 0)
-                        && (9223372036854775808 & rooks.1 != 0)
-                        && (8070450532247928832 & other_attacks == 0))
-                    || (castling_side == Side::Queen
-                        && self.colour == Colour::Black
-                        && castling_rights.black_queen
-                        && (1008806316530991104 & occupancy == 0)
-                        && (72057594037927936 & rooks.1 != 0)
-                        && (2017612633061982208 & other_attacks == 0)))
+                let rights = castling_rights;
+                let (right, own_rooks, king, rook, target) = match (castling_side, self.colour) {
+                    (Side::King, Colour::White) => (rights.white_king, rooks.0, e1, h1, g1),
+                    (Side::Queen, Colour::White) => (rights.white_queen, rooks.0, e1, a1, c1),
+                    (Side::King, Colour::Black) => (rights.black_king, rooks.1, e8, h8, g8),
+                    (Side::Queen, Colour::Black) => (rights.black_queen, rooks.1, e8, a8, c8),
+                };
+                let between = &tables().between[king as usize];
+                let passed = between[target as usize] | 1 << king | 1 << target;
+                if !right
+                    || between[rook as usize] & occupancy != 0
+                    || own_rooks & 1 << rook == 0
+                    || passed & other_attacks != 0
                 {
                     return false;
                 }
@@ -536,38 +463,112 @@ impl ChessMoveBase {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChessMove {
-    base: ChessMoveBase,
+    pub(crate) base: ChessMoveBase,
+    pub(crate) captured: Option<Piece>,
     pub board: Board,
 }
 impl ChessMove {
-    pub fn from_base(
+    /// The square the moving piece starts on. For castling this is the king's square.
+    pub fn source_square(&self) -> Square {
+        Square::ALL[self.base.starting_sqaure as usize]
+    }
+
+    /// The square the moving piece ends up on. For castling this is the king's square.
+    pub fn destination_square(&self) -> Square {
+        Square::ALL[self.base.destination_square as usize]
+    }
+
+    /// The piece being moved. Promotions move a pawn, and castling moves the king.
+    pub fn piece(&self) -> Piece {
+        self.base.piece
+    }
+
+    pub fn colour(&self) -> Colour {
+        self.base.colour
+    }
+
+    /// The piece captured by the move, if any.
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured
+    }
+
+    /// The piece a pawn is promoted to, if the move is a promotion.
+    pub fn promotion(&self) -> Option<Piece> {
+        match self.base.move_type {
+            MoveType::Promotion(piece) => Some(piece),
+            _ => None,
+        }
+    }
+
+    /// The side castled to, if the move is castling.
+    pub fn castling(&self) -> Option<Side> {
+        match self.base.move_type {
+            MoveType::Castling(side) => Some(side),
+            _ => None,
+        }
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        self.base.move_type == MoveType::EnPassent
+    }
+
+    pub fn is_double_pawn_push(&self) -> bool {
+        self.base.move_type == MoveType::DoublePawnPush
+    }
+
+    /// Whether the move puts the opponent in check.
+    pub fn is_check(&self) -> bool {
+        self.board.is_check()
+    }
+
+    pub(crate) fn from_base(
         base: ChessMoveBase,
         board: &Board,
         castling_rights: castling_rights,
         occupancy: u64,
         rooks: (u64, u64),
-        mutate_function: Option<MutateFunction>,
     ) -> Option<Self> {
         if base.is_legal(castling_rights, occupancy, rooks) {
             let mut new_board = *board;
             new_board.base = base.res_board;
             new_board.side_to_move = board.side_to_move.other();
-            new_board.halfmove += 1;
+
+            let captured = match base.move_type {
+                MoveType::EnPassent => Some(Piece::Pawn(base.colour.other())),
+                MoveType::Castling(_) => None,
+                _ => board.piece_at(base.destination_square),
+            };
+
+            // The halfmove clock is reset by pawn moves and captures
+            if let Piece::Pawn(_) = base.piece {
+                new_board.halfmove = 0;
+            } else if captured.is_some() {
+                new_board.halfmove = 0;
+            } else {
+                new_board.halfmove += 1;
+            }
             if base.colour == Colour::Black {
                 new_board.fullmove += 1;
             }
 
-            // Reset killer square
-            new_board.killer_square = -1;
+            // A double push leaves the square behind the pawn to be captured en passant
+            new_board.killer_square = match base.move_type {
+                MoveType::DoublePawnPush => (base.starting_sqaure + base.destination_square) / 2,
+                _ => -1,
+            };
 
-            if let Some(f) = mutate_function {
-                f(&mut new_board);
-            }
+            new_board
+                .castling_rights
+                .update(base.starting_sqaure, base.destination_square);
+
+            new_board.zobrist = zobrist::updated_key(board, &new_board);
+            debug_assert_eq!(new_board.zobrist, zobrist::full_key(&new_board));
 
             Some(Self {
                 base,
+                captured,
                 board: new_board,
             })
         } else {
@@ -576,32 +577,45 @@ impl ChessMove {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Board {
     pub base: bitboard_base,
-    killer_square: i32,
-    castling_rights: castling_rights,
-    side_to_move: Colour,
-    halfmove: i32,
-    fullmove: i32,
+    pub(crate) killer_square: i32,
+    pub(crate) castling_rights: castling_rights,
+    pub(crate) side_to_move: Colour,
+    pub(crate) halfmove: i32,
+    pub(crate) fullmove: i32,
+    // kept up to date by every move, see `zobrist_key`
+    pub(crate) zobrist: u64,
 }
 impl Board {
     pub fn new(
         base: bitboard_base,
-        killer_square: i32,
+        en_passant: Option<Square>,
         castling_rights: castling_rights,
         side_to_move: Colour,
         halfmove: i32,
         fullmove: i32,
     ) -> Self {
-        Self {
+        // moves can only be generated for a board once the attack tables are filled
+        crate::init();
+        let mut res = Self {
             base,
-            killer_square,
+            killer_square: en_passant.map_or(-1, |square| square as i32),
             castling_rights,
             side_to_move,
             halfmove,
             fullmove,
-        }
+            zobrist: 0,
+        };
+        res.zobrist = zobrist::full_key(&res);
+        res
+    }
+
+    /// A 64-bit Zobrist hash key of the position. It covers the pieces, castling rights, side to
+    /// move and en passant square, but not the clocks.
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist
     }
     pub fn view_bitboard(&self, colour: Colour, piece: Piece) {
         let bitboard_maps = match colour {
@@ -618,14 +632,74 @@ impl Board {
             Piece::Rook(_) => bitboard_maps.rooks,
         };
 
-        println!("{}", BitBoard(map));
+        println!("{}", Bitboard(map));
+    }
+
+    /// The squares occupied by a piece.
+    pub fn bitboard(&self, piece: Piece) -> Bitboard {
+        let side = self.base.get_side(piece.colour());
+        Bitboard(match piece {
+            Piece::Pawn(_) => side.pawns,
+            Piece::King(_) => side.king,
+            Piece::Queen(_) => side.queens,
+            Piece::Bishop(_) => side.bishops,
+            Piece::Knight(_) => side.knights,
+            Piece::Rook(_) => side.rooks,
+        })
+    }
+
+    /// The squares occupied by any piece.
+    pub fn occupancy(&self) -> Bitboard {
+        Bitboard(self.base.white_occupied | self.base.black_occupied)
+    }
+
+    /// The square a pawn that has just made a double push can be captured on, whether or not a
+    /// capture is possible.
+    pub fn en_passant(&self) -> Option<Square> {
+        usize::try_from(self.killer_square)
+            .ok()
+            .and_then(Square::from_index)
+    }
+
+    /// Returns the en passant square only if a legal en passant capture onto it exists.
+    pub fn capturable_en_passant(&self) -> Option<Square> {
+        let square = self.en_passant()?;
+        self.generate_legal_moves()
+            .iter()
+            .any(|m| m.base.move_type == MoveType::EnPassent)
+            .then_some(square)
+    }
+
+    /// Returns the piece occupying `square`, if any.
+    pub fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.piece_at(square as i32)
+    }
+
+    pub(crate) fn piece_at(&self, square: i32) -> Option<Piece> {
+        let mask = 1u64 << square;
+        for colour in [Colour::White, Colour::Black] {
+            let side = self.base.get_side(colour);
+            for (bitboard, piece) in [
+                (side.pawns, Piece::Pawn(colour)),
+                (side.king, Piece::King(colour)),
+                (side.queens, Piece::Queen(colour)),
+                (side.bishops, Piece::Bishop(colour)),
+                (side.knights, Piece::Knight(colour)),
+                (side.rooks, Piece::Rook(colour)),
+            ] {
+                if bitboard & mask != 0 {
+                    return Some(piece);
+                }
+            }
+        }
+        None
     }
 
     pub fn generate_legal_moves(&self) -> Vec<ChessMove> {
         let mut res = Vec::new();
-        let occupancy = self.base.black_occupied + self.base.white_occupied;
+        let occupancy = self.base.black_occupied | self.base.white_occupied;
 
-        for (m, f) in self
+        for m in self
             .base
             .get_pseudo_legal_moves(self.side_to_move, self.killer_square)
         {
@@ -635,7 +709,6 @@ impl Board {
                 self.castling_rights,
                 occupancy,
                 (self.base.white.rooks, self.base.black.rooks),
-                f,
             ) {
                 res.push(legal_move);
             }
@@ -643,6 +716,37 @@ impl Board {
         res
     }
 
+    /// Checks whether neither side can possibly checkmate, no matter how badly the other side
+    /// plays. This is the case for K vs K, K and a minor piece vs K, and positions where the only
+    /// pieces besides the kings are bishops that all stand on squares of the same colour.
+    pub fn is_insufficient_material(&self) -> bool {
+        let white = self.base.white;
+        let black = self.base.black;
+        if white.pawns | black.pawns | white.queens | black.queens | white.rooks | black.rooks != 0
+        {
+            return false;
+        }
+
+        let knights = white.knights | black.knights;
+        let bishops = white.bishops | black.bishops;
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+        let light = Bitboard::LIGHT_SQUARES.0;
+        knights == 0 && (bishops & light == 0 || bishops & !light == 0)
+    }
+
+    /// Returns a draw that the side to move may claim in this position, without the game being
+    /// over by itself.
+    pub fn claimable_draw(&self) -> Option<ReasonDraw> {
+        // --- Fifty move rule ---
+        if self.halfmove >= HALFMOVE_CLAIM_LIMIT {
+            Some(ReasonDraw::HalfmoveLimit)
+        } else {
+            None
+        }
+    }
+
     pub fn get_game_state(&self) -> GameState {
         // Even though most positions should return Ongoing, there are some states that
         // should be checked before generating moves since they could yield a finished state
@@ -651,56 +755,67 @@ impl Board {
         // --- Insufficient Material ---
         // Easiest way to skip further calculations is to check if there are pawns
         // on either side. This should skip further calculations in most positions.
-        if self.base.white.pawns + self.base.black.pawns == 0 {
-            unimplemented!("Chance of insufficient material")
+        if self.base.white.pawns | self.base.black.pawns == 0 && self.is_insufficient_material() {
+            return GameState::Finished(FinishedState::Draw(ReasonDraw::InsufficientMaterial));
         }
 
-        // --- Halfmove limit (yet to be implemented) ---
-
-        // --- Resignation & Agreement (yet to be implemented) ---
+        // --- Resignation & Agreement ---
+        // These are decided by the players rather than the position and are handled by `Game`
 
         // From here on out, we require legal moves of this position
         let moves = self.generate_legal_moves();
-        if moves.len() != 0 {
+        if !moves.is_empty() {
             // --- Ongoing ---
             // Easiest way to check if the game is still ongoing is to check if there
             // are any legal moves in the current position.
             // If we have gotten this far in the game state checking and there are
             // legal moves, the game is still ongoing.
+
+            // --- Halfmove limit ---
+            // After 75 moves by each player without a pawn move or capture the game is drawn
+            // automatically. This is checked after the legal moves since a checkmate delivered
+            // on the last move takes precedence.
+            if self.halfmove >= HALFMOVE_LIMIT {
+                return GameState::Finished(FinishedState::Draw(ReasonDraw::HalfmoveLimit));
+            }
             GameState::Ongoing(moves)
         } else {
             // The game is over. Now it's a matter of figuring out why
             // The game has ended in either checkmate or stalemate
-            let side = self.base.get_side(self.side_to_move);
-            let other_side = self.base.get_side(self.side_to_move.other());
+            GameState::Finished(
+                // is the king in check?
+                if !self.is_check() {
+                    // --- Stalemate ---
+                    // If there are no legal moves and the king is not in check, the game ends in
+                    // stalemate
+                    FinishedState::Draw(ReasonDraw::Stalemate)
+                } else {
+                    // --- Checkmate ---
+                    // If there are no legal moves and the king is in check, the game ends in
+                    // checkmate
+                    FinishedState::Win(self.side_to_move.other(), ReasonWin::Checkmate)
+                },
+            )
+        }
+    }
 
-            unsafe {
-                let other_attacks = generateAttackTargets(
-                    other_side,
-                    self.side_to_move.other().as_int(),
-                    self.base.white_occupied + self.base.black_occupied,
-                );
-                GameState::Finished(
-                    // is the king in check?
-                    if side.king & other_attacks == 0 {
-                        // --- Stalemate ---
-                        // If there are no legal moves and the king is not in check, the game ends in
-                        // stalemate
-                        FinishedState::Draw(ReasonDraw::Stalemate)
-                    } else {
-                        // --- Checkmate ---
-                        // If there are no legal moves and the king is in check, the game ends in
-                        // checkmate
-                        FinishedState::Win(self.side_to_move.other(), ReasonWin::Checkmate)
-                    },
-                )
-            }
+    /// Checks whether the king of the side to move is attacked.
+    pub fn is_check(&self) -> bool {
+        let side = self.base.get_side(self.side_to_move);
+        let other_side = self.base.get_side(self.side_to_move.other());
+        unsafe {
+            let other_attacks = generateAttackTargets(
+                other_side,
+                self.side_to_move.other().as_int(),
+                self.base.white_occupied | self.base.black_occupied,
+            );
+            side.king & other_attacks != 0
         }
     }
 }
 impl Default for Board {
     fn default() -> Self {
-        Self::from(START_POSITION)
+        Self::try_from(START_POSITION).unwrap()
     }
 }
 impl Display for Board {
@@ -721,7 +836,7 @@ impl Display for Board {
                     write!(f, ". ")?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         match self.side_to_move {
@@ -729,7 +844,7 @@ impl Display for Board {
             Colour::Black => writeln!(f, "Black to move")?,
         }
 
-        if let Some(square) = to_str(self.killer_square) {
+        if let Some(square) = self.en_passant() {
             writeln!(f, "Board has killer square {square}")?;
         } else {
             writeln!(f, "Board has no killer square")?;
@@ -770,46 +885,3 @@ fn find_on_square(square: i32, white: &Pieces, black: &Pieces) -> Option<Piece>
         None
     }
 }
-
-#[derive(Debug)]
-pub struct BitBoard(pub u64);
-impl Display for BitBoard {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut bits: Vec<char> = format!("{:#0b}", self.0)[2..].chars().collect();
-        for _ in 0..(64 - bits.len()) {
-            bits.insert(0, '0');
-        }
-        let mut ranks: Vec<Vec<char>> = bits.chunks(8).map(|s| s.into()).collect();
-        for rank in &mut ranks {
-            rank.reverse();
-            for square in rank {
-                if *square == '0' {
-                    write!(f, ". ")?;
-                } else {
-                    write!(f, "{square} ")?;
-                }
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
-    }
-}
-impl BitBoard {
-    // Ranks should be ordered 8-1 and squares in rank should be in order a-h
-    pub fn from_ranks(mut ranks: [[u8; 8]; 8]) -> Self {
-        let mut res = 0;
-        let mut exp = 0;
-        ranks.reverse();
-        for rank in ranks {
-            for square in rank {
-                if square == 1 {
-                    // only calculated if sqare is not 0
-                    res += 2u64.pow(exp);
-                }
-                exp += 1;
-            }
-        }
-
-        Self(res)
-    }
-}
@@ -0,0 +1,258 @@
+use crate::{
+    board::{Board, ChessMove, FinishedState, GameState, ReasonDraw, ReasonWin},
+    castling_rights,
+    utils::Colour,
+    Square,
+};
+use std::{error::Error, fmt::Display};
+
+/// Number of times a position has to occur before a draw may be claimed.
+pub const REPETITION_CLAIM_LIMIT: usize = 3;
+/// Number of times a position has to occur before the game is drawn.
+pub const REPETITION_LIMIT: usize = 5;
+
+/// The identity of a position as used by the repetition rules.
+///
+/// Two positions are the same if the same pieces occupy the same squares, the same side is to
+/// move and the same castling rights remain. The en passant square is only taken into account
+/// if an en passant capture is actually possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pieces: [u64; 12],
+    side_to_move: Colour,
+    castling_rights: castling_rights,
+    en_passant: Option<Square>,
+}
+impl From<&Board> for Position {
+    fn from(board: &Board) -> Self {
+        let white = board.base.white;
+        let black = board.base.black;
+        Self {
+            pieces: [
+                white.pawns,
+                white.king,
+                white.queens,
+                white.bishops,
+                white.knights,
+                white.rooks,
+                black.pawns,
+                black.king,
+                black.queens,
+                black.bishops,
+                black.knights,
+                black.rooks,
+            ],
+            side_to_move: board.side_to_move,
+            castling_rights: board.castling_rights,
+            en_passant: board.capturable_en_passant(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    /// The move cannot be played in the current position.
+    IllegalMove,
+    /// The game has already finished.
+    GameFinished,
+    /// The action can only be taken by the side to move.
+    NotYourTurn,
+    /// There is no draw offer from the opponent to respond to.
+    NoDrawOffer,
+    /// A draw offer is already pending.
+    DrawAlreadyOffered,
+    /// Neither the fifty move rule nor the repetition rule applies.
+    NoDrawToClaim,
+}
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IllegalMove => write!(f, "illegal move"),
+            Self::GameFinished => write!(f, "the game has already finished"),
+            Self::NotYourTurn => write!(f, "it is not your turn"),
+            Self::NoDrawOffer => write!(f, "there is no draw offer to respond to"),
+            Self::DrawAlreadyOffered => write!(f, "a draw has already been offered"),
+            Self::NoDrawToClaim => write!(f, "there is no draw to claim"),
+        }
+    }
+}
+impl Error for GameError {}
+
+/// A game played from a starting position, keeping track of the moves played and the positions
+/// that have occured.
+#[derive(Debug, Clone)]
+pub struct Game {
+    start: Board,
+    moves: Vec<ChessMove>,
+    // positions[i] is the position after i moves, so positions[0] is the starting position
+    positions: Vec<Position>,
+    // set when the game is ended by one of the players rather than by the position
+    result: Option<FinishedState>,
+    draw_offer: Option<Colour>,
+}
+impl Game {
+    pub fn new(start: Board) -> Self {
+        Self {
+            start,
+            moves: Vec::new(),
+            positions: vec![Position::from(&start)],
+            result: None,
+            draw_offer: None,
+        }
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    /// The board of the current position.
+    pub fn board(&self) -> &Board {
+        self.moves.last().map_or(&self.start, |m| &m.board)
+    }
+
+    pub fn moves(&self) -> &[ChessMove] {
+        &self.moves
+    }
+
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// Plays a move, which must be one of the legal moves of the current position.
+    pub fn play(&mut self, chess_move: ChessMove) -> Result<(), GameError> {
+        match self.get_game_state() {
+            GameState::Finished(_) => Err(GameError::GameFinished),
+            GameState::Ongoing(moves) => {
+                if !moves.contains(&chess_move) {
+                    return Err(GameError::IllegalMove);
+                }
+                // Making a move instead of accepting declines the opponent's draw offer
+                if self.draw_offer == Some(self.board().side_to_move.other()) {
+                    self.draw_offer = None;
+                }
+                self.positions.push(Position::from(&chess_move.board));
+                self.moves.push(chess_move);
+                Ok(())
+            }
+        }
+    }
+
+    /// Number of times the current position has occured in the game, including now.
+    pub fn repetitions(&self) -> usize {
+        let current = self.positions.last().unwrap();
+        // Positions can only repeat since the last pawn move or capture
+        let since_reset = self.board().halfmove as usize + 1;
+        self.positions
+            .iter()
+            .rev()
+            .take(since_reset)
+            .filter(|p| *p == current)
+            .count()
+    }
+
+    /// Returns a draw that the side to move may claim in the current position.
+    pub fn claimable_draw(&self) -> Option<ReasonDraw> {
+        if self.repetitions() >= REPETITION_CLAIM_LIMIT {
+            Some(ReasonDraw::Repetition)
+        } else {
+            self.board().claimable_draw()
+        }
+    }
+
+    /// The colour that has a pending draw offer, if any.
+    pub fn draw_offer(&self) -> Option<Colour> {
+        self.draw_offer
+    }
+
+    /// `colour` resigns, handing the win to the opponent.
+    pub fn resign(&mut self, colour: Colour) -> Result<FinishedState, GameError> {
+        self.finish(FinishedState::Win(colour.other(), ReasonWin::Resignation))
+    }
+
+    /// `colour` offers a draw, which stays open until the opponent accepts, declines or makes a
+    /// move.
+    pub fn offer_draw(&mut self, colour: Colour) -> Result<(), GameError> {
+        self.ensure_ongoing()?;
+        if self.draw_offer.is_some() {
+            return Err(GameError::DrawAlreadyOffered);
+        }
+        self.draw_offer = Some(colour);
+        Ok(())
+    }
+
+    /// `colour` accepts the draw offered by the opponent.
+    pub fn accept_draw(&mut self, colour: Colour) -> Result<FinishedState, GameError> {
+        self.ensure_ongoing()?;
+        if self.draw_offer != Some(colour.other()) {
+            return Err(GameError::NoDrawOffer);
+        }
+        self.finish(FinishedState::Draw(ReasonDraw::Agreement))
+    }
+
+    /// `colour` declines the draw offered by the opponent.
+    pub fn decline_draw(&mut self, colour: Colour) -> Result<(), GameError> {
+        self.ensure_ongoing()?;
+        if self.draw_offer != Some(colour.other()) {
+            return Err(GameError::NoDrawOffer);
+        }
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// `colour` claims a draw under the fifty move or repetition rule. Only the side to move may
+    /// claim a draw.
+    pub fn claim_draw(&mut self, colour: Colour) -> Result<FinishedState, GameError> {
+        self.ensure_ongoing()?;
+        if colour != self.board().side_to_move {
+            return Err(GameError::NotYourTurn);
+        }
+        match self.claimable_draw() {
+            Some(reason) => self.finish(FinishedState::Draw(reason)),
+            None => Err(GameError::NoDrawToClaim),
+        }
+    }
+
+    fn ensure_ongoing(&self) -> Result<(), GameError> {
+        match self.get_game_state() {
+            GameState::Ongoing(_) => Ok(()),
+            GameState::Finished(_) => Err(GameError::GameFinished),
+        }
+    }
+
+    fn finish(&mut self, result: FinishedState) -> Result<FinishedState, GameError> {
+        self.ensure_ongoing()?;
+        self.result = Some(result);
+        self.draw_offer = None;
+        Ok(result)
+    }
+
+    pub fn get_game_state(&self) -> GameState {
+        // --- Resignation & Agreement ---
+        if let Some(result) = self.result {
+            return GameState::Finished(result);
+        }
+
+        match self.board().get_game_state() {
+            GameState::Ongoing(moves) => {
+                // --- Repetition ---
+                // The position occuring for the fifth time draws the game
+                if self.repetitions() >= REPETITION_LIMIT {
+                    GameState::Finished(FinishedState::Draw(ReasonDraw::Repetition))
+                } else {
+                    GameState::Ongoing(moves)
+                }
+            }
+            finished => finished,
+        }
+    }
+}
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(Board::default())
+    }
+}
+impl From<Board> for Game {
+    fn from(value: Board) -> Self {
+        Self::new(value)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{bishopTargets, rookTargets, Bitboard, Direction, Square};
+use std::sync::OnceLock;
+
+/// Lines between pairs of squares, indexed by the squares in the order of `Square`.
+pub(crate) struct Tables {
+    /// The squares strictly between two squares on a line.
+    pub(crate) between: [[u64; 64]; 64],
+    /// The whole line through two squares, from one edge of the board to the other.
+    pub(crate) line: [[u64; 64]; 64],
+    /// The squares from a square to the edge of the board, indexed by `Direction::index`.
+    pub(crate) rays: [[u64; 8]; 64],
+}
+
+/// The tables, filled from the slider attacks the first time they are needed.
+pub(crate) fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Box<Tables>> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = Box::new(Tables {
+            between: [[0; 64]; 64],
+            line: [[0; 64]; 64],
+            rays: [[0; 8]; 64],
+        });
+        for a in 0..64 {
+            for b in 0..64 {
+                if a == b {
+                    continue;
+                }
+                let (a_bit, b_bit) = (1 << a, 1 << b);
+                // the attacks of a piece on each square, blocked by the other, only overlap in
+                // between, and on an empty board they overlap on the rest of the line
+                for targets in [rookTargets, bishopTargets] {
+                    let from_a = unsafe { targets(a, b_bit) };
+                    if from_a & b_bit != 0 {
+                        tables.between[a as usize][b as usize] =
+                            from_a & unsafe { targets(b, a_bit) };
+                        tables.line[a as usize][b as usize] =
+                            unsafe { targets(a, 0) & targets(b, 0) } | a_bit | b_bit;
+                    }
+                }
+            }
+        }
+        for square in Square::ALL {
+            for direction in Direction::ALL {
+                let Some(next) = square.bitboard().shift(direction).first() else {
+                    continue;
+                };
+                // the ray is the part of the line that can't be reached without passing `next`
+                let from = square as usize;
+                tables.rays[from][direction.index()] = Bitboard(tables.line[from][next as usize])
+                    .iter()
+                    .filter(|&s| {
+                        s == next || tables.between[from][s as usize] & next.bitboard().0 != 0
+                    })
+                    .collect::<Bitboard>()
+                    .0;
+            }
+        }
+        tables
+    })
+}
+
+/// The squares strictly between `a` and `b`, or none if they don't share a file, rank or
+/// diagonal.
+pub fn between(a: Square, b: Square) -> Bitboard {
+    Bitboard(tables().between[a as usize][b as usize])
+}
+
+/// The file, rank or diagonal through `a` and `b`, including both, or none if there is no such
+/// line or the squares are the same.
+pub fn line(a: Square, b: Square) -> Bitboard {
+    Bitboard(tables().line[a as usize][b as usize])
+}
+
+/// The squares a slider on `square` would pass on an empty board going towards `direction`.
+pub fn ray(square: Square, direction: Direction) -> Bitboard {
+    Bitboard(tables().rays[square as usize][direction.index()])
+}
+
+/// Whether the three squares lie on one file, rank or diagonal.
+pub fn aligned(a: Square, b: Square, c: Square) -> bool {
+    line(a, b).contains(c)
+}
+
+/// The number of king moves it takes to get from `a` to `b`.
+pub fn distance(a: Square, b: Square) -> u32 {
+    let (files, ranks) = offsets(a, b);
+    files.max(ranks)
+}
+
+/// The number of steps along files and ranks it takes to get from `a` to `b`.
+pub fn manhattan_distance(a: Square, b: Square) -> u32 {
+    let (files, ranks) = offsets(a, b);
+    files + ranks
+}
+
+fn offsets(a: Square, b: Square) -> (u32, u32) {
+    (
+        a.file().index().abs_diff(b.file().index()) as u32,
+        a.rank().index().abs_diff(b.rank().index()) as u32,
+    )
+}
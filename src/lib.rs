@@ -5,22 +5,50 @@
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
 mod utils;
-use utils::fen::*;
 
+mod bitboard;
 mod board;
-pub use board::{Board, ChessMove, FinishedState, GameState};
+mod game;
+mod geometry;
+mod make_move;
+mod moves;
+mod perft;
+mod pgn;
+mod polyglot;
+mod square;
+mod zobrist;
+pub use bitboard::{Bitboard, Direction, Squares};
+pub use board::{
+    Board, ChessMove, FinishedState, GameState, ReasonDraw, ReasonWin, Side, HALFMOVE_CLAIM_LIMIT,
+    HALFMOVE_LIMIT,
+};
+pub use game::{Game, GameError, Position, REPETITION_CLAIM_LIMIT, REPETITION_LIMIT};
+pub use geometry::{aligned, between, distance, line, manhattan_distance, ray};
+pub use make_move::UndoInfo;
+pub use moves::{Move, MoveList};
+pub use perft::PerftStats;
+pub use pgn::{read_games, PgnError, PgnGame, PgnMove, PgnReader, PgnResult, SEVEN_TAG_ROSTER};
+pub use polyglot::{BookBuilder, BookEntry, BookError, BookOptions, PolyglotBook};
+pub use square::{File, ParseSquareError, Rank, Square};
 pub use utils::{
+    epd::{run_epd_suite, Epd, EpdCase, EpdCheck, EpdError, EpdOp, EpdReport},
     fen::{
-        CASTLE_KINGSIDE_POSITION, CASTLE_QUEENSIDE_POSITION, CHECK_POSITION, CMK_POSITION,
-        KILLER_POSITION, PROMOTION_POSITION, START_POSITION, TRICKY_POSITION,
+        Fen, FenError, FenField, CASTLE_KINGSIDE_POSITION, CASTLE_QUEENSIDE_POSITION,
+        CHECK_POSITION, CMK_POSITION, KILLER_POSITION, PROMOTION_POSITION, START_POSITION,
+        TRICKY_POSITION,
     },
-    Colour,
+    uci::MoveParseError,
+    Colour, Piece,
 };
 
 mod tests;
 
+/// Fills the attack tables of the C library, once per process no matter how often or from how
+/// many threads it is called.
+///
+/// Every `Board` is created through `Board::new`, which calls this, so there is no need to call it
+/// before using the crate.
 pub fn init() {
-    unsafe {
-        init_targets();
-    }
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| unsafe { init_targets() });
 }
@@ -0,0 +1,241 @@
+use crate::{
+    board::{Board, ChessMove, Side},
+    castling_rights,
+    moves::{Move, MoveList},
+    utils::{Colour, Piece},
+    zobrist,
+};
+
+/// What `Board::make_move` can't work out backwards from the move alone, for
+/// `Board::unmake_move` to restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoInfo {
+    captured: Option<Piece>,
+    castling_rights: castling_rights,
+    killer_square: i32,
+    halfmove: i32,
+    zobrist: u64,
+}
+
+/// Everything about a move needed to play it, whichever way the move is represented.
+struct MoveInfo {
+    source: i32,
+    destination: i32,
+    piece: Piece,
+    colour: Colour,
+    captured: Option<Piece>,
+    promotion: Option<Piece>,
+    castling: Option<Side>,
+    en_passant: bool,
+    double_pawn_push: bool,
+}
+impl From<&ChessMove> for MoveInfo {
+    fn from(chess_move: &ChessMove) -> Self {
+        Self {
+            source: chess_move.source_square() as i32,
+            destination: chess_move.destination_square() as i32,
+            piece: chess_move.piece(),
+            colour: chess_move.colour(),
+            captured: chess_move.captured(),
+            promotion: chess_move.promotion(),
+            castling: chess_move.castling(),
+            en_passant: chess_move.is_en_passant(),
+            double_pawn_push: chess_move.is_double_pawn_push(),
+        }
+    }
+}
+
+impl Board {
+    /// Adds a piece to an empty square, or removes it from the square it stands on.
+    fn toggle_piece(&mut self, piece: Piece, square: i32) {
+        let (side, occupied) = match piece.colour() {
+            Colour::White => (&mut self.base.white, &mut self.base.white_occupied),
+            Colour::Black => (&mut self.base.black, &mut self.base.black_occupied),
+        };
+        let bitboard = match piece {
+            Piece::Pawn(_) => &mut side.pawns,
+            Piece::King(_) => &mut side.king,
+            Piece::Queen(_) => &mut side.queens,
+            Piece::Bishop(_) => &mut side.bishops,
+            Piece::Knight(_) => &mut side.knights,
+            Piece::Rook(_) => &mut side.rooks,
+        };
+        *bitboard ^= 1 << square;
+        *occupied ^= 1 << square;
+        self.zobrist ^= zobrist::piece_key(piece, square);
+    }
+
+    /// Moves the pieces involved in a move. Doing it twice takes the move back.
+    fn toggle_move(&mut self, info: &MoveInfo) {
+        let MoveInfo {
+            source,
+            destination,
+            colour,
+            ..
+        } = *info;
+
+        if let Some(captured) = info.captured {
+            let square = match (info.en_passant, colour) {
+                // the captured pawn stands behind the square the capturing pawn moves to
+                (true, Colour::White) => destination - 8,
+                (true, Colour::Black) => destination + 8,
+                (false, _) => destination,
+            };
+            self.toggle_piece(captured, square);
+        }
+        self.toggle_piece(info.piece, source);
+        self.toggle_piece(info.promotion.unwrap_or(info.piece), destination);
+        match info.castling {
+            Some(Side::King) => {
+                self.toggle_piece(Piece::Rook(colour), source + 3);
+                self.toggle_piece(Piece::Rook(colour), source + 1);
+            }
+            Some(Side::Queen) => {
+                self.toggle_piece(Piece::Rook(colour), source - 4);
+                self.toggle_piece(Piece::Rook(colour), source - 1);
+            }
+            None => {}
+        }
+    }
+
+    /// Plays a legal move of this position in place, which avoids copying the board. The
+    /// returned `UndoInfo` takes the move back with `unmake_move`.
+    ///
+    /// The result is the same board as `chess_move.board`, which remains the way to look at a
+    /// position without changing this one.
+    pub fn make_move(&mut self, chess_move: &ChessMove) -> UndoInfo {
+        self.make_info(&MoveInfo::from(chess_move))
+    }
+
+    /// Takes back the move last played with `make_move`, restoring the board exactly.
+    pub fn unmake_move(&mut self, chess_move: &ChessMove, undo: UndoInfo) {
+        self.unmake_info(&MoveInfo::from(chess_move), undo);
+    }
+
+    /// Like `make_move`, for a compact move of this position.
+    ///
+    /// Returns `None` and leaves the board unchanged if the move is not legal here, such as a
+    /// move kept from another position. Checking this generates the moves of the position.
+    pub fn make(&mut self, chess_move: Move) -> Option<UndoInfo> {
+        let mut list = MoveList::new();
+        self.generate_moves(&mut list);
+        if !list.contains(&chess_move) {
+            return None;
+        }
+        Some(self.make_unchecked(chess_move))
+    }
+
+    /// Plays a compact move that must at least be pseudo-legal in this position.
+    pub(crate) fn make_unchecked(&mut self, chess_move: Move) -> UndoInfo {
+        let colour = self.side_to_move;
+        let destination = chess_move.destination_square() as i32;
+        let captured = if chess_move.is_en_passant() {
+            Some(Piece::Pawn(colour.other()))
+        } else if chess_move.castling().is_some() {
+            None
+        } else {
+            self.piece_at(destination)
+        };
+        let info = MoveInfo {
+            source: chess_move.source_square() as i32,
+            destination,
+            piece: self
+                .piece_at(chess_move.source_square() as i32)
+                .expect("a move starts from an occupied square"),
+            colour,
+            captured,
+            promotion: chess_move.promotion(colour),
+            castling: chess_move.castling(),
+            en_passant: chess_move.is_en_passant(),
+            double_pawn_push: chess_move.is_double_pawn_push(),
+        };
+        self.make_info(&info)
+    }
+
+    /// Like `unmake_move`, for a compact move played with `make`.
+    ///
+    /// Returns `false` and leaves the board unchanged if the move can't have been the last one
+    /// played, as the piece it moved is not on its destination square.
+    pub fn unmake(&mut self, chess_move: Move, undo: UndoInfo) -> bool {
+        let colour = self.side_to_move.other();
+        let source = chess_move.source_square() as i32;
+        let destination = chess_move.destination_square() as i32;
+        let promotion = chess_move.promotion(colour);
+        let Some(moved) = self.piece_at(destination) else {
+            return false;
+        };
+        if moved.colour() != colour
+            || promotion.is_some_and(|piece| piece != moved)
+            || self.piece_at(source).is_some()
+        {
+            return false;
+        }
+        let info = MoveInfo {
+            source,
+            destination,
+            piece: match promotion {
+                Some(_) => Piece::Pawn(colour),
+                None => moved,
+            },
+            colour,
+            captured: undo.captured,
+            promotion,
+            castling: chess_move.castling(),
+            en_passant: chess_move.is_en_passant(),
+            double_pawn_push: chess_move.is_double_pawn_push(),
+        };
+        self.unmake_info(&info, undo);
+        true
+    }
+
+    fn make_info(&mut self, info: &MoveInfo) -> UndoInfo {
+        let undo = UndoInfo {
+            captured: info.captured,
+            castling_rights: self.castling_rights,
+            killer_square: self.killer_square,
+            halfmove: self.halfmove,
+            zobrist: self.zobrist,
+        };
+        let colour = info.colour;
+        self.zobrist ^= zobrist::state_key(self);
+
+        self.toggle_move(info);
+
+        // --- Castling rights ---
+        self.castling_rights.update(info.source, info.destination);
+
+        // --- En passant square ---
+        self.killer_square = if info.double_pawn_push {
+            (info.source + info.destination) / 2
+        } else {
+            -1
+        };
+
+        // --- Clocks ---
+        if matches!(info.piece, Piece::Pawn(_)) || info.captured.is_some() {
+            self.halfmove = 0;
+        } else {
+            self.halfmove += 1;
+        }
+        if colour == Colour::Black {
+            self.fullmove += 1;
+        }
+
+        self.side_to_move = colour.other();
+        self.zobrist ^= zobrist::state_key(self);
+        debug_assert_eq!(self.zobrist, zobrist::full_key(self));
+        undo
+    }
+
+    fn unmake_info(&mut self, info: &MoveInfo, undo: UndoInfo) {
+        self.toggle_move(info);
+        self.castling_rights = undo.castling_rights;
+        self.killer_square = undo.killer_square;
+        self.halfmove = undo.halfmove;
+        if info.colour == Colour::Black {
+            self.fullmove -= 1;
+        }
+        self.side_to_move = info.colour;
+        self.zobrist = undo.zobrist;
+    }
+}
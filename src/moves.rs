@@ -0,0 +1,501 @@
+use crate::{
+    bishopTargets,
+    board::{Board, ChessMove, Side},
+    geometry::tables,
+    kingTargets, knightTargets, pawnAttackTargets, pawnTargets, queenTargets, rookTargets,
+    utils::{squares::*, Colour, Piece},
+    Square,
+};
+use std::{fmt::Display, ops::Deref};
+
+/// A move packed into 16 bits: the source square in bits 0-5, the destination square in bits
+/// 6-11 and the kind of move in bits 12-15.
+///
+/// Unlike `ChessMove` it does not carry the resulting board, so it only makes sense together
+/// with the position it was generated for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+// The kinds of moves. Captures have the capture bit set, and promotions the promotion bit with
+// the piece in the lowest two bits.
+const QUIET: u16 = 0;
+const DOUBLE_PAWN_PUSH: u16 = 1;
+const KING_CASTLE: u16 = 2;
+const QUEEN_CASTLE: u16 = 3;
+const CAPTURE: u16 = 4;
+const EN_PASSANT: u16 = 5;
+const PROMOTION: u16 = 8;
+const PROMOTION_PIECES: [u16; 4] = [0, 1, 2, 3];
+
+impl Move {
+    pub(crate) fn new(source: i32, destination: i32, kind: u16) -> Self {
+        Self(source as u16 | (destination as u16) << 6 | kind << 12)
+    }
+
+    pub fn source_square(&self) -> Square {
+        Square::ALL[(self.0 & 0o77) as usize]
+    }
+
+    pub fn destination_square(&self) -> Square {
+        Square::ALL[(self.0 >> 6 & 0o77) as usize]
+    }
+
+    fn kind(&self) -> u16 {
+        self.0 >> 12
+    }
+
+    /// Whether the move captures a piece, including en passant.
+    pub fn is_capture(&self) -> bool {
+        self.kind() & CAPTURE != 0
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        self.kind() == EN_PASSANT
+    }
+
+    pub fn is_double_pawn_push(&self) -> bool {
+        self.kind() == DOUBLE_PAWN_PUSH
+    }
+
+    /// The side castled to, if the move is castling.
+    pub fn castling(&self) -> Option<Side> {
+        match self.kind() {
+            KING_CASTLE => Some(Side::King),
+            QUEEN_CASTLE => Some(Side::Queen),
+            _ => None,
+        }
+    }
+
+    /// The piece a pawn of the given colour is promoted to, if the move is a promotion.
+    pub fn promotion(&self, colour: Colour) -> Option<Piece> {
+        if self.kind() & PROMOTION == 0 {
+            return None;
+        }
+        Some(match self.kind() & 0b11 {
+            0 => Piece::Knight(colour),
+            1 => Piece::Bishop(colour),
+            2 => Piece::Rook(colour),
+            _ => Piece::Queen(colour),
+        })
+    }
+}
+
+impl From<&ChessMove> for Move {
+    fn from(chess_move: &ChessMove) -> Self {
+        let capture = if chess_move.captured().is_some() {
+            CAPTURE
+        } else {
+            QUIET
+        };
+        let kind = match (chess_move.castling(), chess_move.promotion()) {
+            (Some(Side::King), _) => KING_CASTLE,
+            (Some(Side::Queen), _) => QUEEN_CASTLE,
+            (_, Some(piece)) => {
+                let piece = match piece {
+                    Piece::Knight(_) => 0,
+                    Piece::Bishop(_) => 1,
+                    Piece::Rook(_) => 2,
+                    _ => 3,
+                };
+                PROMOTION | capture | piece
+            }
+            _ if chess_move.is_en_passant() => EN_PASSANT,
+            _ if chess_move.is_double_pawn_push() => DOUBLE_PAWN_PUSH,
+            _ => capture,
+        };
+        Self::new(
+            chess_move.source_square() as i32,
+            chess_move.destination_square() as i32,
+            kind,
+        )
+    }
+}
+
+/// Writes the move in UCI long algebraic notation, like `ChessMove` does.
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.source_square(), self.destination_square())?;
+        if let Some(piece) = self.promotion(Colour::Black) {
+            write!(f, "{}", piece.as_char())?;
+        }
+        Ok(())
+    }
+}
+
+/// Room for the 218 legal moves of the richest known position, and then some for pseudo-legal
+/// moves.
+const CAPACITY: usize = 256;
+
+/// A list of moves with a fixed capacity, which lives on the stack.
+#[derive(Debug, Clone, Copy)]
+pub struct MoveList {
+    moves: [Move; CAPACITY],
+    len: usize,
+}
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move::default(); CAPACITY],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, chess_move: Move) {
+        self.moves[self.len] = chess_move;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &Self::Target {
+        &self.moves[..self.len]
+    }
+}
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl PartialEq for MoveList {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+impl Eq for MoveList {}
+
+/// Pushes a move for every square set in `targets`.
+fn push_moves(list: &mut MoveList, source: i32, mut targets: u64, enemies: u64) {
+    while targets != 0 {
+        let target = targets.trailing_zeros() as i32;
+        let kind = if enemies & 1 << target != 0 {
+            CAPTURE
+        } else {
+            QUIET
+        };
+        list.push(Move::new(source, target, kind));
+        targets &= targets - 1;
+    }
+}
+
+/// Pushes the pawn moves to the squares set in `targets`, which become promotions on the back
+/// ranks.
+fn push_pawn_moves(list: &mut MoveList, source: i32, mut targets: u64, enemies: u64) {
+    let back_ranks: u64 = 0xFF000000000000FF;
+    while targets != 0 {
+        let target = targets.trailing_zeros() as i32;
+        let capture = if enemies & 1 << target != 0 {
+            CAPTURE
+        } else {
+            QUIET
+        };
+        if back_ranks & 1 << target != 0 {
+            for piece in PROMOTION_PIECES {
+                list.push(Move::new(source, target, PROMOTION | capture | piece));
+            }
+        } else if (target - source).abs() == 16 {
+            list.push(Move::new(source, target, DOUBLE_PAWN_PUSH));
+        } else {
+            list.push(Move::new(source, target, capture));
+        }
+        targets &= targets - 1;
+    }
+}
+
+impl Board {
+    /// Fills `list` with the pseudo-legal moves of the position: moves that follow the rules of
+    /// how pieces move, but may leave the king in check. Castling is only generated when the
+    /// squares between king and rook are empty.
+    pub fn generate_pseudo_legal_moves(&self, list: &mut MoveList) {
+        list.clear();
+        let colour = self.side_to_move;
+        let own = self.base.get_side(colour);
+        let (own_occupied, enemies) = match colour {
+            Colour::White => (self.base.white_occupied, self.base.black_occupied),
+            Colour::Black => (self.base.black_occupied, self.base.white_occupied),
+        };
+        let occupancy = own_occupied | enemies;
+
+        // --- Pawn Moves ---
+        let mut pawns = own.pawns;
+        while pawns != 0 {
+            let source = pawns.trailing_zeros() as i32;
+            let targets =
+                unsafe { pawnTargets(source, colour.as_int(), occupancy) } & !own_occupied;
+            push_pawn_moves(list, source, targets, enemies);
+            if self.killer_square >= 0
+                && unsafe { pawnAttackTargets(source, colour.as_int()) } & 1 << self.killer_square
+                    != 0
+            {
+                list.push(Move::new(source, self.killer_square, EN_PASSANT));
+            }
+            pawns &= pawns - 1;
+        }
+
+        // --- Piece Moves ---
+        for (mut bitboard, targets) in [
+            (
+                own.knights,
+                knightTargets as unsafe extern "C" fn(i32) -> u64,
+            ),
+            (own.king, kingTargets),
+        ] {
+            while bitboard != 0 {
+                let source = bitboard.trailing_zeros() as i32;
+                let targets = unsafe { targets(source) } & !own_occupied;
+                push_moves(list, source, targets, enemies);
+                bitboard &= bitboard - 1;
+            }
+        }
+        for (mut bitboard, targets) in [
+            (
+                own.bishops,
+                bishopTargets as unsafe extern "C" fn(i32, u64) -> u64,
+            ),
+            (own.rooks, rookTargets),
+            (own.queens, queenTargets),
+        ] {
+            while bitboard != 0 {
+                let source = bitboard.trailing_zeros() as i32;
+                let targets = unsafe { targets(source, occupancy) } & !own_occupied;
+                push_moves(list, source, targets, enemies);
+                bitboard &= bitboard - 1;
+            }
+        }
+
+        // --- Castling ---
+        let rights = self.castling_rights;
+        let (king_side, queen_side, king) = match colour {
+            Colour::White => (rights.white_king, rights.white_queen, e1),
+            Colour::Black => (rights.black_king, rights.black_queen, e8),
+        };
+        let between = &tables().between[king as usize];
+        if own.king & 1 << king != 0 {
+            if king_side && occupancy & between[king as usize + 3] == 0 {
+                list.push(Move::new(king, king + 2, KING_CASTLE));
+            }
+            if queen_side && occupancy & between[king as usize - 4] == 0 {
+                list.push(Move::new(king, king - 2, QUEEN_CASTLE));
+            }
+        }
+    }
+
+    /// Fills `list` with the legal moves of the position, without allocating.
+    ///
+    /// Instead of trying out every pseudo-legal move, the pieces giving check and the pinned
+    /// pieces are found once, and every piece is limited to the moves that deal with them.
+    pub fn generate_moves(&self, list: &mut MoveList) {
+        list.clear();
+        let colour = self.side_to_move;
+        let enemy = colour.other();
+        let own = self.base.get_side(colour);
+        let (own_occupied, enemies) = match colour {
+            Colour::White => (self.base.white_occupied, self.base.black_occupied),
+            Colour::Black => (self.base.black_occupied, self.base.white_occupied),
+        };
+        let occupancy = own_occupied | enemies;
+        if own.king == 0 {
+            return;
+        }
+        let king = own.king.trailing_zeros() as i32;
+        let between = &tables().between;
+
+        // --- King Moves ---
+        // the king is left out of the occupancy, so that it can't hide behind itself from a
+        // slider checking it
+        let without_king = occupancy & !own.king;
+        let mut targets = unsafe { kingTargets(king) } & !own_occupied;
+        while targets != 0 {
+            let target = targets.trailing_zeros() as i32;
+            if self.attackers(target, enemy, without_king) == 0 {
+                push_moves(list, king, 1 << target, enemies);
+            }
+            targets &= targets - 1;
+        }
+
+        // --- Checks ---
+        // in double check only the king can move, otherwise the other pieces must capture the
+        // checking piece or block its line
+        let checkers = self.attackers(king, enemy, occupancy);
+        let check_mask = match checkers.count_ones() {
+            0 => !0,
+            1 => checkers | between[king as usize][checkers.trailing_zeros() as usize],
+            _ => return,
+        };
+
+        // --- Pins ---
+        // a piece alone between the king and an enemy slider may only move along their line
+        let mut pinned = 0;
+        let mut pin_rays = [!0u64; 64];
+        let enemy_side = self.base.get_side(enemy);
+        let mut snipers = unsafe {
+            rookTargets(king, enemies) & (enemy_side.rooks | enemy_side.queens)
+                | bishopTargets(king, enemies) & (enemy_side.bishops | enemy_side.queens)
+        };
+        while snipers != 0 {
+            let sniper = snipers.trailing_zeros() as i32;
+            let line = between[king as usize][sniper as usize];
+            let blockers = line & occupancy;
+            if blockers.count_ones() == 1 && blockers & own_occupied != 0 {
+                pinned |= blockers;
+                pin_rays[blockers.trailing_zeros() as usize] = line | 1 << sniper;
+            }
+            snipers &= snipers - 1;
+        }
+        let allowed = |source: i32| check_mask & pin_rays[source as usize];
+
+        // --- Pawn Moves ---
+        let mut pawns = own.pawns;
+        while pawns != 0 {
+            let source = pawns.trailing_zeros() as i32;
+            let targets = unsafe { pawnTargets(source, colour.as_int(), occupancy) }
+                & !own_occupied
+                & allowed(source);
+            push_pawn_moves(list, source, targets, enemies);
+            if self.killer_square >= 0
+                && unsafe { pawnAttackTargets(source, colour.as_int()) } & 1 << self.killer_square
+                    != 0
+            {
+                // en passant removes two pieces from a line at once, so the king is checked on
+                // the board after the capture, without the captured pawn
+                let captured = match colour {
+                    Colour::White => self.killer_square - 8,
+                    Colour::Black => self.killer_square + 8,
+                };
+                let after = occupancy ^ (1 << source | 1 << captured) | 1 << self.killer_square;
+                if self.attackers(king, enemy, after) & after == 0 {
+                    list.push(Move::new(source, self.killer_square, EN_PASSANT));
+                }
+            }
+            pawns &= pawns - 1;
+        }
+
+        // --- Piece Moves ---
+        // pinned knights can't move, as no knight move stays on a line through its square
+        let mut knights = own.knights & !pinned;
+        while knights != 0 {
+            let source = knights.trailing_zeros() as i32;
+            let targets = unsafe { knightTargets(source) } & !own_occupied & check_mask;
+            push_moves(list, source, targets, enemies);
+            knights &= knights - 1;
+        }
+        for (mut bitboard, targets) in [
+            (
+                own.bishops,
+                bishopTargets as unsafe extern "C" fn(i32, u64) -> u64,
+            ),
+            (own.rooks, rookTargets),
+            (own.queens, queenTargets),
+        ] {
+            while bitboard != 0 {
+                let source = bitboard.trailing_zeros() as i32;
+                let targets =
+                    unsafe { targets(source, occupancy) } & !own_occupied & allowed(source);
+                push_moves(list, source, targets, enemies);
+                bitboard &= bitboard - 1;
+            }
+        }
+
+        // --- Castling ---
+        // the king may not castle out of, through or into check
+        let rights = self.castling_rights;
+        let (king_side, queen_side, home) = match colour {
+            Colour::White => (rights.white_king, rights.white_queen, e1),
+            Colour::Black => (rights.black_king, rights.black_queen, e8),
+        };
+        if checkers == 0 && king == home {
+            let safe = |square: i32| self.attackers(square, enemy, occupancy) == 0;
+            if king_side
+                && own.rooks & 1 << (home + 3) != 0
+                && occupancy & between[home as usize][home as usize + 3] == 0
+                && safe(home + 1)
+                && safe(home + 2)
+            {
+                list.push(Move::new(home, home + 2, KING_CASTLE));
+            }
+            if queen_side
+                && own.rooks & 1 << (home - 4) != 0
+                && occupancy & between[home as usize][home as usize - 4] == 0
+                && safe(home - 1)
+                && safe(home - 2)
+            {
+                list.push(Move::new(home, home - 2, QUEEN_CASTLE));
+            }
+        }
+    }
+
+    /// Checks whether a pseudo-legal move of this position is legal. Moves that don't start
+    /// from a piece of the side to move are never legal.
+    pub fn is_legal(&self, chess_move: Move) -> bool {
+        let colour = self.side_to_move;
+        let rights = self.castling_rights;
+        let source = chess_move.source_square() as i32;
+        if self
+            .piece_at(source)
+            .is_none_or(|piece| piece.colour() != colour)
+        {
+            return false;
+        }
+        if let Some(side) = chess_move.castling() {
+            // castling needs the right and the rook, and the king may not castle out of, through
+            // or into check
+            let (right, rook, passed) = match (side, colour) {
+                (Side::King, Colour::White) => (rights.white_king, h1, source + 1),
+                (Side::Queen, Colour::White) => (rights.white_queen, a1, source - 1),
+                (Side::King, Colour::Black) => (rights.black_king, h8, source + 1),
+                (Side::Queen, Colour::Black) => (rights.black_queen, a8, source - 1),
+            };
+            if !right
+                || self.base.get_side(colour).rooks & 1 << rook == 0
+                || self.is_attacked(source, colour.other())
+                || self.is_attacked(passed, colour.other())
+            {
+                return false;
+            }
+        }
+
+        let mut board = *self;
+        board.make_unchecked(chess_move);
+        let king = board.base.get_side(colour).king;
+        king != 0 && !board.is_attacked(king.trailing_zeros() as i32, colour.other())
+    }
+
+    /// Checks whether any piece of `colour` attacks `square`.
+    pub(crate) fn is_attacked(&self, square: i32, colour: Colour) -> bool {
+        let occupancy = self.base.white_occupied | self.base.black_occupied;
+        self.attackers(square, colour, occupancy) != 0
+    }
+
+    /// The pieces of `colour` attacking `square`, with sliders blocked by `occupancy` rather than
+    /// the pieces on the board.
+    pub(crate) fn attackers(&self, square: i32, colour: Colour, occupancy: u64) -> u64 {
+        let side = self.base.get_side(colour);
+        unsafe {
+            // a pawn of the other colour on the square would attack the pawns attacking it
+            pawnAttackTargets(square, colour.other().as_int()) & side.pawns
+                | knightTargets(square) & side.knights
+                | kingTargets(square) & side.king
+                | bishopTargets(square, occupancy) & (side.bishops | side.queens)
+                | rookTargets(square, occupancy) & (side.rooks | side.queens)
+        }
+    }
+
+    /// Finds the legal `ChessMove` matching a compact move of this position.
+    pub fn chess_move(&self, chess_move: Move) -> Option<ChessMove> {
+        self.generate_legal_moves()
+            .into_iter()
+            .find(|m| Move::from(m) == chess_move)
+    }
+}
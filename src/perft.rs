@@ -0,0 +1,80 @@
+use crate::board::{Board, ChessMove, MoveType};
+use std::ops::AddAssign;
+
+/// Move counts of a perft search, broken down the same way as the published perft results.
+/// Everything but `nodes` counts the moves made on the last ply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub checkmates: u64,
+}
+impl AddAssign for PerftStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.nodes += rhs.nodes;
+        self.captures += rhs.captures;
+        self.en_passants += rhs.en_passants;
+        self.castles += rhs.castles;
+        self.promotions += rhs.promotions;
+        self.checks += rhs.checks;
+        self.checkmates += rhs.checkmates;
+    }
+}
+impl PerftStats {
+    fn leaf(chess_move: &ChessMove) -> Self {
+        let mut res = Self {
+            nodes: 1,
+            ..Default::default()
+        };
+        if chess_move.captured.is_some() {
+            res.captures += 1;
+        }
+        match chess_move.base.move_type {
+            MoveType::EnPassent => res.en_passants += 1,
+            MoveType::Castling(_) => res.castles += 1,
+            MoveType::Promotion(_) => res.promotions += 1,
+            MoveType::Standard | MoveType::DoublePawnPush => {}
+        }
+        if chess_move.board.is_check() {
+            res.checks += 1;
+            if chess_move.board.generate_legal_moves().is_empty() {
+                res.checkmates += 1;
+            }
+        }
+        res
+    }
+}
+
+impl Board {
+    /// Counts the leaf nodes of the move tree `depth` plies deep.
+    pub fn perft(&self, depth: u32) -> PerftStats {
+        let mut res = PerftStats::default();
+        if depth == 0 {
+            res.nodes = 1;
+            return res;
+        }
+        for (_, stats) in self.divide(depth) {
+            res += stats;
+        }
+        res
+    }
+
+    /// Like `perft`, but with the counts split up by the legal moves of this position.
+    pub fn divide(&self, depth: u32) -> Vec<(ChessMove, PerftStats)> {
+        self.generate_legal_moves()
+            .into_iter()
+            .map(|chess_move| {
+                let stats = if depth <= 1 {
+                    PerftStats::leaf(&chess_move)
+                } else {
+                    chess_move.board.perft(depth - 1)
+                };
+                (chess_move, stats)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,148 @@
+mod reader;
+mod writer;
+
+pub use reader::{read_games, PgnReader};
+
+use crate::{
+    board::{Board, ChessMove},
+    game::{Game, GameError},
+    utils::{fen::FenError, uci::MoveParseError},
+};
+use std::{error::Error, fmt::Display, time::Duration};
+
+/// The tags every PGN game is expected to have, in the order they are written.
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// The result of a game as written in PGN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgnResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// The game is still going on, or the result is unknown.
+    Unknown,
+}
+impl PgnResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WhiteWins => "1-0",
+            Self::BlackWins => "0-1",
+            Self::Draw => "1/2-1/2",
+            Self::Unknown => "*",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "1-0" => Some(Self::WhiteWins),
+            "0-1" => Some(Self::BlackWins),
+            "1/2-1/2" => Some(Self::Draw),
+            "*" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+}
+impl Display for PgnResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A move in PGN movetext together with its annotations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub chess_move: ChessMove,
+    /// Numeric annotation glyphs, with "!" and "?" style suffixes converted to their NAG.
+    pub nags: Vec<u8>,
+    /// Comments written before the move. These only occur at the start of a game or variation.
+    pub comments_before: Vec<String>,
+    pub comments: Vec<String>,
+    /// The time left on the mover's clock after the move, from a `[%clk]` command in a comment.
+    pub clock: Option<Duration>,
+    /// Alternatives to this move, each starting from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+impl From<ChessMove> for PgnMove {
+    fn from(chess_move: ChessMove) -> Self {
+        Self {
+            chess_move,
+            nags: Vec::new(),
+            comments_before: Vec::new(),
+            comments: Vec::new(),
+            clock: None,
+            variations: Vec::new(),
+        }
+    }
+}
+
+/// A game read from or to be written to PGN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnGame {
+    /// Tag pairs in the order they appear.
+    pub tags: Vec<(String, String)>,
+    /// The starting position, taken from the FEN tag if there is one.
+    pub start: Board,
+    /// The main line.
+    pub moves: Vec<PgnMove>,
+    pub result: PgnResult,
+}
+impl PgnGame {
+    /// Returns the value of the first tag with the given name.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets the value of a tag, adding it if the game does not have it yet.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Replays the main line as a `Game`.
+    pub fn game(&self) -> Result<Game, GameError> {
+        let mut game = Game::new(self.start);
+        for m in &self.moves {
+            game.play(m.chess_move)?;
+        }
+        Ok(game)
+    }
+}
+
+#[derive(Debug)]
+pub enum PgnError {
+    Io(std::io::Error),
+    /// A tag pair that is not of the form `[Name "value"]`.
+    InvalidTag(String),
+    InvalidFen(FenError),
+    InvalidMove(MoveParseError),
+    /// A token that does not belong in movetext.
+    UnexpectedToken(String),
+    /// A variation is closed without being opened, left open, or has no move to branch from.
+    UnbalancedVariation,
+    UnterminatedComment,
+}
+impl Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::InvalidTag(s) => write!(f, "invalid tag pair '{s}'"),
+            Self::InvalidFen(e) => write!(f, "invalid FEN tag: {e}"),
+            Self::InvalidMove(e) => write!(f, "{e}"),
+            Self::UnexpectedToken(s) => write!(f, "unexpected token '{s}'"),
+            Self::UnbalancedVariation => write!(f, "unbalanced variation"),
+            Self::UnterminatedComment => write!(f, "unterminated comment"),
+        }
+    }
+}
+impl Error for PgnError {}
+impl From<std::io::Error> for PgnError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,390 @@
+use super::{PgnError, PgnGame, PgnMove, PgnResult};
+use crate::board::Board;
+use std::{
+    io::{BufRead, Lines},
+    time::Duration,
+};
+
+/// Reads games from PGN one at a time.
+///
+/// Every item is one game. A game that cannot be parsed yields an error, after which reading
+/// continues with the next game.
+pub struct PgnReader<R: BufRead> {
+    lines: Lines<R>,
+    // the first line of the next game's tag section, read while looking for the end of a game
+    next_line: Option<String>,
+}
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            next_line: None,
+        }
+    }
+
+    /// Collects the text of the next game, which ends where the tag section of the following
+    /// game starts.
+    fn next_game_text(&mut self) -> Option<Result<String, PgnError>> {
+        let mut text = String::new();
+        let mut seen_movetext = false;
+        let mut in_comment = false;
+        loop {
+            let line = match self.next_line.take().map(Ok).or_else(|| self.lines.next()) {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => break,
+            };
+            let trimmed = line.trim();
+            if !in_comment {
+                // lines starting with '%' are escaped and ignored
+                if line.starts_with('%') {
+                    continue;
+                }
+                if trimmed.starts_with('[') {
+                    if seen_movetext {
+                        self.next_line = Some(line);
+                        break;
+                    }
+                } else if !trimmed.is_empty() {
+                    seen_movetext = true;
+                }
+            }
+            in_comment = ends_in_comment(&line, in_comment);
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        if text.trim().is_empty() {
+            None
+        } else {
+            Some(Ok(text))
+        }
+    }
+}
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_game_text()
+            .map(|text| text.and_then(|text| parse_game(&text)))
+    }
+}
+
+/// Reads all games from a string of PGN.
+pub fn read_games(pgn: &str) -> PgnReader<&[u8]> {
+    PgnReader::new(pgn.as_bytes())
+}
+
+/// Checks whether a line leaves a brace comment open, given whether one was open at its start.
+fn ends_in_comment(line: &str, mut in_comment: bool) -> bool {
+    for c in line.chars() {
+        match c {
+            '}' if in_comment => in_comment = false,
+            '{' if !in_comment => in_comment = true,
+            // the rest of the line is a comment, which can't contain braces
+            ';' if !in_comment => break,
+            _ => {}
+        }
+    }
+    in_comment
+}
+
+fn parse_game(text: &str) -> Result<PgnGame, PgnError> {
+    // --- Tag pairs ---
+    let mut tags = Vec::new();
+    let mut rest = text.trim_start();
+    while rest.starts_with('[') {
+        let (name, value, remainder) = parse_tag(rest)?;
+        tags.push((name, value));
+        rest = remainder.trim_start();
+    }
+
+    // An initial position is given by the FEN tag, which should come with SetUp "1"
+    let start = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => Board::try_from(fen.as_str()).map_err(PgnError::InvalidFen)?,
+        None => Board::default(),
+    };
+
+    // --- Movetext ---
+    let (moves, result) = parse_movetext(rest, start)?;
+    let result = result
+        .or_else(|| {
+            tags.iter()
+                .find(|(name, _)| name == "Result")
+                .and_then(|(_, value)| PgnResult::parse(value))
+        })
+        .unwrap_or(PgnResult::Unknown);
+
+    Ok(PgnGame {
+        tags,
+        start,
+        moves,
+        result,
+    })
+}
+
+/// Parses a tag pair at the start of `s`, returning its name, value and the rest of `s`.
+fn parse_tag(s: &str) -> Result<(String, String, &str), PgnError> {
+    let end = s.find('\n').unwrap_or(s.len());
+    let invalid = || PgnError::InvalidTag(s[..end].trim().to_string());
+
+    let inner = s[1..].trim_start();
+    let name_len = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .ok_or_else(invalid)?;
+    if name_len == 0 {
+        return Err(invalid());
+    }
+    let name = inner[..name_len].to_string();
+
+    let mut chars = inner[name_len..].trim_start().char_indices();
+    if chars.next().map(|(_, c)| c) != Some('"') {
+        return Err(invalid());
+    }
+    let after_name = inner[name_len..].trim_start();
+    let mut value = String::new();
+    let mut close = None;
+    let mut escaped = false;
+    for (i, c) in chars {
+        match c {
+            _ if escaped => {
+                value.push(c);
+                escaped = false;
+            }
+            '\\' => escaped = true,
+            '"' => {
+                close = Some(i);
+                break;
+            }
+            '\n' => return Err(invalid()),
+            _ => value.push(c),
+        }
+    }
+    let after_value = after_name[close.ok_or_else(invalid)? + 1..].trim_start();
+    let remainder = after_value.strip_prefix(']').ok_or_else(invalid)?;
+    Ok((name, value, remainder))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Move(&'a str),
+    Comment(&'a str),
+    Nag(u8),
+    StartVariation,
+    EndVariation,
+    Result(PgnResult),
+}
+
+/// Splits movetext into tokens, dropping move numbers.
+fn tokenize(movetext: &str) -> Result<Vec<Token<'_>>, PgnError> {
+    let mut res = Vec::new();
+    let mut rest = movetext;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(res);
+        };
+        let len = match c {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::UnterminatedComment)?;
+                res.push(Token::Comment(rest[1..end].trim()));
+                end + 1
+            }
+            ';' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                res.push(Token::Comment(rest[1..end].trim()));
+                end
+            }
+            '(' => {
+                res.push(Token::StartVariation);
+                1
+            }
+            ')' => {
+                res.push(Token::EndVariation);
+                1
+            }
+            '$' => {
+                let end = rest[1..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(rest.len(), |i| i + 1);
+                let nag = rest[1..end]
+                    .parse()
+                    .map_err(|_| PgnError::UnexpectedToken(rest[..end].to_string()))?;
+                res.push(Token::Nag(nag));
+                end
+            }
+            '!' | '?' => {
+                let end = rest
+                    .find(|c: char| c != '!' && c != '?')
+                    .unwrap_or(rest.len());
+                let nag = match &rest[..end] {
+                    "!" => 1,
+                    "?" => 2,
+                    "!!" => 3,
+                    "??" => 4,
+                    "!?" => 5,
+                    "?!" => 6,
+                    s => return Err(PgnError::UnexpectedToken(s.to_string())),
+                };
+                res.push(Token::Nag(nag));
+                end
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{};()$!?".contains(c))
+                    .unwrap_or(rest.len());
+                let symbol = &rest[..end];
+                if let Some(result) = PgnResult::parse(symbol) {
+                    res.push(Token::Result(result));
+                } else if symbol == "e.p." {
+                    // en passant captures are sometimes marked, which SAN does not need
+                } else {
+                    // move numbers such as "12." or "12..." may be glued to the move
+                    let san = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+                    let san = if san.len() < symbol.len() && san.starts_with('.') {
+                        san.trim_start_matches('.')
+                    } else if san.is_empty() {
+                        return Err(PgnError::UnexpectedToken(symbol.to_string()));
+                    } else {
+                        symbol
+                    };
+                    if !san.is_empty() {
+                        res.push(Token::Move(san));
+                    }
+                }
+                end
+            }
+        };
+        rest = &rest[len..];
+    }
+}
+
+/// A line of moves being read, either the main line or a variation.
+struct Line {
+    start: Board,
+    moves: Vec<PgnMove>,
+    // comments that will be attached to the next move
+    comments_before: Vec<String>,
+}
+impl Line {
+    fn new(start: Board) -> Self {
+        Self {
+            start,
+            moves: Vec::new(),
+            comments_before: Vec::new(),
+        }
+    }
+
+    fn board(&self) -> &Board {
+        self.moves
+            .last()
+            .map_or(&self.start, |m| &m.chess_move.board)
+    }
+}
+
+fn parse_movetext(
+    movetext: &str,
+    start: Board,
+) -> Result<(Vec<PgnMove>, Option<PgnResult>), PgnError> {
+    // the main line is at the bottom of the stack, with open variations above it
+    let mut lines = vec![Line::new(start)];
+    let mut result = None;
+
+    for token in tokenize(movetext)? {
+        let line = lines.last_mut().unwrap();
+        match token {
+            Token::Move(san) => {
+                let chess_move = line.board().parse_san(san).map_err(PgnError::InvalidMove)?;
+                let mut pgn_move = PgnMove::from(chess_move);
+                pgn_move.comments_before = std::mem::take(&mut line.comments_before);
+                line.moves.push(pgn_move);
+            }
+            Token::Comment(comment) => {
+                // comments may be wrapped over several lines
+                let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                match line.moves.last_mut() {
+                    Some(last) => match extract_clock(&comment) {
+                        Some((clock, rest)) => {
+                            last.clock = Some(clock);
+                            if !rest.is_empty() {
+                                last.comments.push(rest);
+                            }
+                        }
+                        None => last.comments.push(comment),
+                    },
+                    None => line.comments_before.push(comment),
+                }
+            }
+            Token::Nag(nag) => match line.moves.last_mut() {
+                Some(last) => last.nags.push(nag),
+                None => return Err(PgnError::UnexpectedToken(format!("${nag}"))),
+            },
+            Token::StartVariation => {
+                // a variation replaces the last move, so it starts from the position before it
+                let start = match line.moves.len() {
+                    0 => return Err(PgnError::UnbalancedVariation),
+                    1 => line.start,
+                    n => line.moves[n - 2].chess_move.board,
+                };
+                lines.push(Line::new(start));
+            }
+            Token::EndVariation => {
+                if lines.len() < 2 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                let variation = lines.pop().unwrap();
+                let parent = lines.last_mut().unwrap();
+                parent
+                    .moves
+                    .last_mut()
+                    .unwrap()
+                    .variations
+                    .push(variation.moves);
+            }
+            Token::Result(r) => {
+                if lines.len() > 1 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                result = Some(r);
+            }
+        }
+    }
+
+    if lines.len() > 1 {
+        return Err(PgnError::UnbalancedVariation);
+    }
+    Ok((lines.pop().unwrap().moves, result))
+}
+
+/// Takes a `[%clk h:mm:ss]` command out of a comment, returning the clock time and what is left of
+/// the comment.
+fn extract_clock(comment: &str) -> Option<(Duration, String)> {
+    let start = comment.find("[%clk ")?;
+    let end = start + comment[start..].find(']')?;
+    let clock = parse_clock(comment[start + 6..end].trim())?;
+    let rest = format!("{} {}", &comment[..start], &comment[end + 1..]);
+    Some((clock, rest.trim().to_string()))
+}
+
+/// Parses a clock time written as `h:mm:ss` with an optional fraction of a second.
+fn parse_clock(s: &str) -> Option<Duration> {
+    let mut fields = s.split(':');
+    let (hours, minutes, seconds) = (fields.next()?, fields.next()?, fields.next()?);
+    if fields.next().is_some() {
+        return None;
+    }
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // the clock comes from untrusted files, so a time too long to count is no clock at all
+    let secs = hours
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(3600)?
+        .checked_add(minutes.parse::<u64>().ok()?.checked_mul(60)?)?
+        .checked_add(seconds.parse::<u64>().ok()?)?;
+    let millis = format!("{fraction:0<3}").parse::<u64>().ok()?;
+    Some(Duration::from_secs(secs) + Duration::from_millis(millis))
+}
@@ -0,0 +1,182 @@
+use super::{PgnGame, PgnMove, PgnResult, SEVEN_TAG_ROSTER};
+use crate::{
+    board::{Board, FinishedState, GameState},
+    game::Game,
+    utils::Colour,
+};
+use std::{fmt::Display, time::Duration};
+
+/// Movetext lines are wrapped to stay within this many characters.
+const LINE_WIDTH: usize = 80;
+
+impl From<FinishedState> for PgnResult {
+    fn from(value: FinishedState) -> Self {
+        match value {
+            FinishedState::Win(Colour::White, _) => Self::WhiteWins,
+            FinishedState::Win(Colour::Black, _) => Self::BlackWins,
+            FinishedState::Draw(_) => Self::Draw,
+        }
+    }
+}
+impl From<&GameState> for PgnResult {
+    fn from(value: &GameState) -> Self {
+        match value {
+            GameState::Ongoing(_) => Self::Unknown,
+            GameState::Finished(state) => Self::from(*state),
+        }
+    }
+}
+
+impl From<&Game> for PgnGame {
+    fn from(game: &Game) -> Self {
+        let result = PgnResult::from(&game.get_game_state());
+        let mut tags: Vec<(String, String)> = SEVEN_TAG_ROSTER
+            .iter()
+            .map(|&name| (name.to_string(), default_tag(name).to_string()))
+            .collect();
+        tags[6].1 = result.to_string();
+        if *game.start() != Board::default() {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), game.start().to_fen()));
+        }
+
+        Self {
+            tags,
+            start: *game.start(),
+            moves: game.moves().iter().map(|&m| PgnMove::from(m)).collect(),
+            result,
+        }
+    }
+}
+
+/// The value written for a tag of the seven tag roster that is missing.
+fn default_tag(name: &str) -> &'static str {
+    match name {
+        "Date" => "????.??.??",
+        "Result" => "*",
+        _ => "?",
+    }
+}
+
+/// Writes the game as PGN in export format.
+///
+/// The seven tag roster comes first, with missing tags filled in and `Result` taken from the
+/// game's result, followed by the remaining tags in order and the movetext.
+impl Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // --- Tag pairs ---
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.as_str(),
+                _ => self.tag(name).unwrap_or(default_tag(name)),
+            };
+            write_tag(f, name, value)?;
+        }
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.contains(&name.as_str()) {
+                write_tag(f, name, value)?;
+            }
+        }
+        writeln!(f)?;
+
+        // --- Movetext ---
+        let mut tokens = Vec::new();
+        movetext_tokens(&mut tokens, &self.start, &self.moves);
+        tokens.push(self.result.to_string());
+
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > LINE_WIDTH {
+                writeln!(f)?;
+                line_len = 0;
+            } else if line_len > 0 {
+                write!(f, " ")?;
+                line_len += 1;
+            }
+            write!(f, "{token}")?;
+            line_len += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+fn write_tag(f: &mut std::fmt::Formatter<'_>, name: &str, value: &str) -> std::fmt::Result {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{name} \"{value}\"]")
+}
+
+/// Splits a line of moves into the words of its movetext.
+fn movetext_tokens(tokens: &mut Vec<String>, start: &Board, moves: &[PgnMove]) {
+    let mut board = *start;
+    // black's moves are numbered at the start of a line and after comments and variations
+    let mut number_black = true;
+
+    for m in moves {
+        for comment in &m.comments_before {
+            comment_tokens(tokens, comment);
+            number_black = true;
+        }
+        match board.side_to_move {
+            Colour::White => tokens.push(format!("{}.", board.fullmove)),
+            Colour::Black if number_black => tokens.push(format!("{}...", board.fullmove)),
+            Colour::Black => {}
+        }
+        tokens.push(board.san(&m.chess_move));
+        tokens.extend(m.nags.iter().map(|nag| format!("${nag}")));
+        number_black = false;
+
+        if let Some(clock) = m.clock {
+            comment_tokens(tokens, &format!("[%clk {}]", Clock(clock)));
+            number_black = true;
+        }
+        for comment in &m.comments {
+            comment_tokens(tokens, comment);
+            number_black = true;
+        }
+        for variation in &m.variations {
+            let first = tokens.len();
+            movetext_tokens(tokens, &board, variation);
+            if tokens.len() == first {
+                tokens.push("()".to_string());
+            } else {
+                tokens[first].insert(0, '(');
+                tokens.last_mut().unwrap().push(')');
+            }
+            number_black = true;
+        }
+
+        board = m.chess_move.board;
+    }
+}
+
+/// Splits a comment into words so that it can be wrapped like the rest of the movetext. A `}` would
+/// end the comment early, so it is left out.
+fn comment_tokens(tokens: &mut Vec<String>, comment: &str) {
+    let first = tokens.len();
+    tokens.extend(
+        comment
+            .split_whitespace()
+            .map(|word| word.replace('}', ""))
+            .filter(|word| !word.is_empty()),
+    );
+    if tokens.len() == first {
+        tokens.push("{}".to_string());
+    } else {
+        tokens[first].insert(0, '{');
+        tokens.last_mut().unwrap().push('}');
+    }
+}
+
+/// Formats a clock time as `h:mm:ss`, with fractions of a second only when there are any.
+struct Clock(Duration);
+impl Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.0.as_secs();
+        write!(f, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)?;
+        let millis = self.0.subsec_millis();
+        if millis != 0 {
+            write!(f, ".{}", format!("{millis:03}").trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    board::{Board, ChessMove, Side},
+    utils::{squares::*, Piece},
+};
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+/// The size of an entry in a `.bin` book file.
+const ENTRY_SIZE: usize = 16;
+
+/// An entry of a Polyglot book: a move for the position with the given key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    /// The move in Polyglot's encoding. Castling is written as the king capturing its own rook.
+    pub raw_move: u16,
+    /// How good the move is, relative to the other moves of the position.
+    pub weight: u16,
+    pub learn: u32,
+}
+impl BookEntry {
+    /// Reads an entry from its big-endian representation in a book file.
+    pub fn from_bytes(bytes: [u8; ENTRY_SIZE]) -> Self {
+        let [k0, k1, k2, k3, k4, k5, k6, k7, m0, m1, w0, w1, l0, l1, l2, l3] = bytes;
+        Self {
+            key: u64::from_be_bytes([k0, k1, k2, k3, k4, k5, k6, k7]),
+            raw_move: u16::from_be_bytes([m0, m1]),
+            weight: u16::from_be_bytes([w0, w1]),
+            learn: u32::from_be_bytes([l0, l1, l2, l3]),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut res = [0; ENTRY_SIZE];
+        res[..8].copy_from_slice(&self.key.to_be_bytes());
+        res[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        res[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        res[12..].copy_from_slice(&self.learn.to_be_bytes());
+        res
+    }
+
+    /// Encodes a move the way Polyglot does, which writes castling as the king taking its rook.
+    pub fn encode_move(chess_move: &ChessMove) -> u16 {
+        let source = chess_move.source_square() as i32;
+        let destination = match chess_move.castling() {
+            Some(Side::King) => source + 3,
+            Some(Side::Queen) => source - 4,
+            None => chess_move.destination_square() as i32,
+        };
+        let promotion = match chess_move.promotion() {
+            Some(Piece::Knight(_)) => 1,
+            Some(Piece::Bishop(_)) => 2,
+            Some(Piece::Rook(_)) => 3,
+            Some(Piece::Queen(_)) => 4,
+            _ => 0,
+        };
+        (promotion << 12 | source << 6 | destination) as u16
+    }
+
+    /// Finds the legal move of `board` the entry describes.
+    pub fn chess_move(&self, board: &Board) -> Option<ChessMove> {
+        let raw = self.raw_move as i32;
+        let destination = raw & 0o77;
+        let source = (raw >> 6) & 0o77;
+        let promotion = match (raw >> 12) & 0b111 {
+            0 => None,
+            1 => Some(Piece::Knight(board.side_to_move)),
+            2 => Some(Piece::Bishop(board.side_to_move)),
+            3 => Some(Piece::Rook(board.side_to_move)),
+            4 => Some(Piece::Queen(board.side_to_move)),
+            _ => return None,
+        };
+
+        let moves = board.generate_legal_moves();
+        // castling is only encoded as king takes rook, which can't be a legal move otherwise
+        let castling = match (source, destination) {
+            (s, d) if s == e1 && d == h1 || s == e8 && d == h8 => Some(Side::King),
+            (s, d) if s == e1 && d == a1 || s == e8 && d == a8 => Some(Side::Queen),
+            _ => None,
+        };
+        if let Some(side) = castling {
+            if let Some(m) = moves
+                .iter()
+                .find(|m| m.source_square() as i32 == source && m.castling() == Some(side))
+            {
+                return Some(*m);
+            }
+        }
+        moves.into_iter().find(|m| {
+            m.source_square() as i32 == source
+                && m.destination_square() as i32 == destination
+                && m.promotion() == promotion
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(io::Error),
+    /// The size of the book is not a multiple of the size of an entry.
+    InvalidLength(usize),
+}
+impl Display for BookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::InvalidLength(len) => write!(
+                f,
+                "a book of {len} bytes does not consist of {ENTRY_SIZE} byte entries"
+            ),
+        }
+    }
+}
+impl Error for BookError {}
+impl From<io::Error> for BookError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// An opening book in the Polyglot `.bin` format, with its entries sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+}
+impl PolyglotBook {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, BookError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BookError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(BookError::InvalidLength(bytes.len()));
+        }
+        let entries = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| BookEntry::from_bytes(chunk.try_into().unwrap()))
+            .collect();
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Creates a book from entries in any order.
+    pub fn from_entries(mut entries: Vec<BookEntry>) -> Self {
+        // books are written sorted, in which case this is cheap
+        entries.sort_by_key(|entry| entry.key);
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[BookEntry] {
+        &self.entries
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.entries.iter().flat_map(BookEntry::to_bytes).collect()
+    }
+
+    /// Writes the book to a `.bin` file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Looks up the entries of a position by binary search.
+    pub fn lookup(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = start + self.entries[start..].partition_point(|entry| entry.key == key);
+        &self.entries[start..end]
+    }
+
+    /// The book moves of the position with their weights. Entries that do not describe a legal
+    /// move are left out.
+    pub fn moves(&self, board: &Board) -> Vec<(ChessMove, u16)> {
+        self.lookup(board.polyglot_key())
+            .iter()
+            .filter_map(|entry| Some((entry.chess_move(board)?, entry.weight)))
+            .collect()
+    }
+
+    /// The book move with the highest weight, preferring the first one on ties.
+    pub fn best_move(&self, board: &Board) -> Option<ChessMove> {
+        self.moves(board)
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, weight)| *weight)
+            .map(|(chess_move, _)| chess_move)
+    }
+
+    /// Picks a book move with a probability proportional to its weight.
+    ///
+    /// `random` should be uniformly distributed, e.g. taken from a random number generator.
+    pub fn weighted_move(&self, board: &Board, random: u64) -> Option<ChessMove> {
+        let moves = self.moves(board);
+        let total: u64 = moves.iter().map(|(_, weight)| *weight as u64).sum();
+        if total == 0 {
+            return moves.first().map(|(chess_move, _)| *chess_move);
+        }
+        let mut choice = random % total;
+        for (chess_move, weight) in moves {
+            if choice < weight as u64 {
+                return Some(chess_move);
+            }
+            choice -= weight as u64;
+        }
+        unreachable!("the choice is less than the sum of the weights")
+    }
+}
@@ -0,0 +1,150 @@
+use super::{BookEntry, PolyglotBook};
+use crate::{
+    pgn::{PgnError, PgnGame, PgnResult},
+    utils::Colour,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Settings for building an opening book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookOptions {
+    /// Only the first this many plies of every game are added.
+    pub max_ply: usize,
+    /// Whether to add the moves played by white.
+    pub white: bool,
+    /// Whether to add the moves played by black.
+    pub black: bool,
+    /// Moves played in fewer games than this are left out.
+    pub min_games: u32,
+    /// The score a move gets for each game won by the side that played it.
+    pub win_score: u32,
+    pub draw_score: u32,
+    pub loss_score: u32,
+}
+impl Default for BookOptions {
+    /// The scoring Polyglot uses: two points for a win and one for a draw.
+    fn default() -> Self {
+        Self {
+            max_ply: 20,
+            white: true,
+            black: true,
+            min_games: 1,
+            win_score: 2,
+            draw_score: 1,
+            loss_score: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MoveStats {
+    games: u32,
+    score: u64,
+}
+
+/// Collects moves from games and turns them into a Polyglot book.
+#[derive(Debug, Clone, Default)]
+pub struct BookBuilder {
+    options: BookOptions,
+    // keyed by the Polyglot key of the position and the encoded move
+    stats: HashMap<(u64, u16), MoveStats>,
+}
+impl BookBuilder {
+    pub fn new(options: BookOptions) -> Self {
+        Self {
+            options,
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Adds the main line of a game. Games without a result are skipped, as their moves can't be
+    /// scored.
+    pub fn add_game(&mut self, game: &PgnGame) {
+        let winner = match game.result {
+            PgnResult::WhiteWins => Some(Colour::White),
+            PgnResult::BlackWins => Some(Colour::Black),
+            PgnResult::Draw => None,
+            PgnResult::Unknown => return,
+        };
+
+        // a move that is played twice in the same position still only counts once per game
+        let mut seen = HashSet::new();
+        let mut board = game.start;
+        for pgn_move in game.moves.iter().take(self.options.max_ply) {
+            let mover = board.side_to_move;
+            let included = match mover {
+                Colour::White => self.options.white,
+                Colour::Black => self.options.black,
+            };
+            let key = (
+                board.polyglot_key(),
+                BookEntry::encode_move(&pgn_move.chess_move),
+            );
+            if included && seen.insert(key) {
+                let stats = self.stats.entry(key).or_default();
+                stats.games += 1;
+                stats.score += match winner {
+                    Some(colour) if colour == mover => self.options.win_score,
+                    Some(_) => self.options.loss_score,
+                    None => self.options.draw_score,
+                } as u64;
+            }
+            board = pgn_move.chess_move.board;
+        }
+    }
+
+    /// Adds every game that could be read, returning the errors of the games that could not.
+    pub fn add_games<I>(&mut self, games: I) -> Vec<PgnError>
+    where
+        I: IntoIterator<Item = Result<PgnGame, PgnError>>,
+    {
+        let mut errors = Vec::new();
+        for game in games {
+            match game {
+                Ok(game) => self.add_game(&game),
+                Err(e) => errors.push(e),
+            }
+        }
+        errors
+    }
+
+    /// Builds the book, with the moves of every position sorted by weight.
+    ///
+    /// Moves that never scored are left out. When scores don't fit into the 16 bits of a weight,
+    /// the weights of the position are scaled down together.
+    pub fn build(&self) -> PolyglotBook {
+        let mut positions: HashMap<u64, Vec<(u16, u64)>> = HashMap::new();
+        for (&(key, raw_move), stats) in &self.stats {
+            if stats.games >= self.options.min_games && stats.score > 0 {
+                positions
+                    .entry(key)
+                    .or_default()
+                    .push((raw_move, stats.score));
+            }
+        }
+
+        let mut entries = Vec::new();
+        for (key, mut moves) in positions {
+            moves.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let max_score = moves[0].1;
+            let max_weight = u16::MAX as u64;
+            for (raw_move, score) in moves {
+                let weight = if max_score > max_weight {
+                    // scaled scores are rounded up so that no move ends up with no weight
+                    (score * max_weight).div_ceil(max_score)
+                } else {
+                    score
+                };
+                entries.push(BookEntry {
+                    key,
+                    raw_move,
+                    weight: weight as u16,
+                    learn: 0,
+                });
+            }
+        }
+        // the best moves of a position come first, as `from_entries` keeps their order
+        entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.weight.cmp(&a.weight)));
+        PolyglotBook::from_entries(entries)
+    }
+}
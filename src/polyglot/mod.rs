@@ -0,0 +1,76 @@
+mod book;
+mod builder;
+mod random;
+
+pub use book::{BookEntry, BookError, PolyglotBook};
+pub use builder::{BookBuilder, BookOptions};
+
+use crate::{
+    board::Board,
+    pawnAttackTargets,
+    utils::{Colour, Piece},
+};
+use random::RANDOM64;
+
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// The index of a piece in the Random64 table, which alternates black and white pieces.
+fn piece_kind(piece: Piece) -> usize {
+    let (kind, colour) = match piece {
+        Piece::Pawn(c) => (0, c),
+        Piece::Knight(c) => (1, c),
+        Piece::Bishop(c) => (2, c),
+        Piece::Rook(c) => (3, c),
+        Piece::Queen(c) => (4, c),
+        Piece::King(c) => (5, c),
+    };
+    kind * 2 + colour.as_int() as usize
+}
+
+impl Board {
+    /// The hash key of the position as used by Polyglot opening books.
+    ///
+    /// Unlike `zobrist_key`, the en passant square only counts when a pawn of the side to move
+    /// stands next to the pawn that just made a double push, whether or not the capture is legal.
+    pub fn polyglot_key(&self) -> u64 {
+        let mut res = 0;
+        for square in 0..64 {
+            if let Some(piece) = self.piece_at(square) {
+                res ^= RANDOM64[64 * piece_kind(piece) + square as usize];
+            }
+        }
+
+        let rights = self.castling_rights;
+        for (i, right) in [
+            rights.white_king,
+            rights.white_queen,
+            rights.black_king,
+            rights.black_queen,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if right {
+                res ^= RANDOM64[CASTLING_OFFSET + i];
+            }
+        }
+
+        if self.killer_square >= 0 {
+            let pawns = self.base.get_side(self.side_to_move).pawns;
+            // the squares a pawn of the side to move could capture onto the square from
+            let attackers = unsafe {
+                pawnAttackTargets(self.killer_square, self.side_to_move.other().as_int())
+            };
+            if pawns & attackers != 0 {
+                res ^= RANDOM64[EN_PASSANT_OFFSET + self.killer_square as usize % 8];
+            }
+        }
+
+        if self.side_to_move == Colour::White {
+            res ^= RANDOM64[TURN_OFFSET];
+        }
+        res
+    }
+}
@@ -0,0 +1,94 @@
+use crate::board::Board;
+use crate::utils::{Colour, Piece};
+use crate::{Bitboard, Direction, File, Rank, Square};
+
+#[test]
+fn test_square_parse() {
+    for square in Square::ALL {
+        assert_eq!(Ok(square), square.to_string().parse());
+    }
+    assert_eq!("e4", Square::E4.to_string());
+    assert_eq!(Square::new(File::E, Rank::Fourth), Square::E4);
+    assert!("e9".parse::<Square>().is_err());
+    assert!("i1".parse::<Square>().is_err());
+    assert!("e".parse::<Square>().is_err());
+    assert!("e44".parse::<Square>().is_err());
+}
+
+#[test]
+fn test_square_coordinates() {
+    assert_eq!(File::C, Square::C7.file());
+    assert_eq!(Rank::Seventh, Square::C7.rank());
+    assert_eq!(50, Square::C7.index());
+    assert_eq!(Some(Square::H8), Square::from_index(63));
+    assert_eq!(None, Square::from_index(64));
+    assert!(File::D.bitboard().contains(Square::D5));
+    assert!(!Rank::Second.bitboard().contains(Square::D5));
+}
+
+#[test]
+fn test_bitboard_iteration() {
+    let bitboard: Bitboard = [Square::H7, Square::A1, Square::D4].into_iter().collect();
+    assert_eq!(3, bitboard.count());
+    assert_eq!(3, bitboard.iter().len());
+    assert_eq!(Some(Square::A1), bitboard.first());
+    assert_eq!(
+        vec![Square::A1, Square::D4, Square::H7],
+        bitboard.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(None, Bitboard::EMPTY.first());
+    assert_eq!(64, Bitboard::FULL.iter().count());
+}
+
+#[test]
+fn test_bitboard_shift() {
+    let edges = Bitboard::FILE_A | Bitboard::FILE_H;
+    assert!(Bitboard::FILE_A.shift(Direction::West).is_empty());
+    assert!(Bitboard::FILE_H.shift(Direction::SouthEast).is_empty());
+    assert!(edges.shift(Direction::East).contains(Square::B1));
+    assert!(!edges.shift(Direction::East).contains(Square::A2));
+    assert!(!edges.shift(Direction::NorthWest).contains(Square::H2));
+    assert!(Bitboard::RANK_8.shift(Direction::North).is_empty());
+    assert_eq!(
+        Bitboard::from(Square::E5),
+        Bitboard::from(Square::D4).shift(Direction::NorthEast)
+    );
+}
+
+#[test]
+fn test_bitboard_lines() {
+    assert_eq!(
+        Bitboard::FULL,
+        Bitboard::FILES
+            .into_iter()
+            .fold(Bitboard::EMPTY, |a, b| a | b)
+    );
+    assert_eq!(
+        Bitboard::FULL,
+        Bitboard::DIAGONALS
+            .into_iter()
+            .fold(Bitboard::EMPTY, |a, b| a | b)
+    );
+    assert_eq!(8, Bitboard::DIAGONALS[7].count());
+    assert!(Bitboard::DIAGONALS[7].contains(Square::A1));
+    assert!(Bitboard::DIAGONALS[7].contains(Square::H8));
+    assert!(Bitboard::ANTI_DIAGONALS[7].contains(Square::H1));
+    assert!(Bitboard::ANTI_DIAGONALS[7].contains(Square::A8));
+    assert_eq!(32, Bitboard::LIGHT_SQUARES.count());
+    assert!(Bitboard::DARK_SQUARES.contains(Square::A1));
+    assert!(Bitboard::LIGHT_SQUARES.contains(Square::H1));
+}
+
+#[test]
+fn test_board_accessors() {
+    let board =
+        Board::try_from("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3").unwrap();
+    assert_eq!(Some(Square::D6), board.en_passant());
+    assert_eq!(Some(Piece::Pawn(Colour::White)), board.piece_on(Square::E5));
+    assert_eq!(None, board.piece_on(Square::E4));
+    assert_eq!(32, board.occupancy().count());
+    assert_eq!(
+        Bitboard::from(Square::E1),
+        board.bitboard(Piece::King(Colour::White))
+    );
+}
@@ -0,0 +1,68 @@
+use super::{find_move, init};
+use crate::board::{Board, Side};
+use crate::utils::fen::*;
+use crate::utils::squares::*;
+use crate::utils::{Colour, Piece};
+
+#[test]
+fn test_move_accessors() {
+    init();
+    let board = Board::try_from(START_POSITION).unwrap();
+    let m = find_move(&board, e2, e4, None);
+    assert_eq!(Piece::Pawn(Colour::White), m.piece());
+    assert_eq!(Colour::White, m.colour());
+    assert!(m.is_double_pawn_push());
+    assert!(!m.is_en_passant());
+    assert!(!m.is_check());
+    assert_eq!(None, m.captured());
+    assert_eq!(None, m.castling());
+    assert!(!find_move(&board, e2, e3, None).is_double_pawn_push());
+
+    let board = Board::try_from(KILLER_POSITION).unwrap();
+    let m = find_move(&board, f5, e6, None);
+    assert!(m.is_en_passant());
+    assert_eq!(Some(Piece::Pawn(Colour::Black)), m.captured());
+
+    let m = find_move(&board, g7, h8, Some(Piece::Queen(Colour::White)));
+    assert_eq!(Piece::Pawn(Colour::White), m.piece());
+    assert_eq!(Some(Piece::Rook(Colour::Black)), m.captured());
+    assert_eq!(Some(Piece::Knight(Colour::White)), {
+        find_move(&board, g7, g8, Some(Piece::Knight(Colour::White))).promotion()
+    });
+
+    let board = Board::try_from(PROMOTION_POSITION).unwrap();
+    assert!(find_move(&board, b7, b8, Some(Piece::Queen(Colour::White))).is_check());
+    assert!(!find_move(&board, b7, b8, Some(Piece::Bishop(Colour::White))).is_check());
+}
+
+#[test]
+fn test_castling_accessors() {
+    init();
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    let m = find_move(&board, e1, g1, None);
+    assert_eq!(Some(Side::King), m.castling());
+    assert_eq!(Piece::King(Colour::White), m.piece());
+    let m = find_move(&board, e1, c1, None);
+    assert_eq!(Some(Side::Queen), m.castling());
+
+    let board = Board::try_from("r3k2r/8/8/8/8/8/8/4K3 b kq - 0 1").unwrap();
+    assert_eq!(Some(Side::King), find_move(&board, e8, g8, None).castling());
+    assert_eq!(
+        Some(Side::Queen),
+        find_move(&board, e8, c8, None).castling()
+    );
+}
+
+/// Boards built without FEN may hold castling rights for a king that has left its square.
+#[test]
+fn test_castling_needs_king_on_starting_square() {
+    let board = Board::try_from("4k3/8/8/8/8/8/8/3K3R w - - 0 1").unwrap();
+    let mut rights = board.castling_rights;
+    rights.white_king = true;
+    let board = Board::new(board.base, None, rights, Colour::White, 0, 1);
+    assert!(board
+        .generate_legal_moves()
+        .iter()
+        .all(|m| m.castling().is_none()));
+    assert!(board.parse_uci_move("e1g1").is_err());
+}
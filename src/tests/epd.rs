@@ -0,0 +1,160 @@
+use super::init;
+use crate::board::{Board, ChessMove, GameState};
+use crate::utils::epd::*;
+use crate::utils::fen::FenError;
+use crate::utils::uci::MoveParseError;
+
+const SUITE: &str = r#"# perft and tactics
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id "start"; D1 20; D2 400; D3 8902; D5 4865609;
+r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - id "kiwipete"; D1 48; D2 2039;
+rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - id "fool"; bm Qh4#; am Ke7; c0 "mate in one";
+
+4k3/8/8/8/8/8/8/4K3 w - - id "wrong count"; D1 6;
+4k3/8/8/8/8/8/8/4K3 w - - bm Ke9;
+"#;
+
+#[test]
+fn test_epd_operations() {
+    init();
+    let epd = Epd::try_from(
+        "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - bm Qh4# Qg5; am Ke7; \
+         id \"fool's mate\"; c3 \"two words\"; acd 12; ce -32000; D2 300; pv Qh4; noop",
+    )
+    .unwrap();
+    assert_eq!(
+        Board::try_from("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 1").unwrap(),
+        epd.board
+    );
+    assert_eq!(Some("fool's mate"), epd.id());
+
+    let san = |s| epd.board.parse_san(s).unwrap();
+    assert_eq!(
+        vec![
+            EpdOp::BestMoves(vec![san("Qh4"), san("Qg5")]),
+            EpdOp::AvoidMoves(vec![san("Ke7")]),
+            EpdOp::Id("fool's mate".to_string()),
+            EpdOp::Comment(3, "two words".to_string()),
+            EpdOp::AnalysisDepth(12),
+            EpdOp::CentipawnEvaluation(-32000),
+            EpdOp::Perft {
+                depth: 2,
+                nodes: 300
+            },
+            EpdOp::Other("pv".to_string(), vec!["Qh4".to_string()]),
+            EpdOp::Other("noop".to_string(), vec![]),
+        ],
+        epd.ops
+    );
+
+    // writing the record gives the same operations back
+    assert_eq!(epd, Epd::try_from(epd.to_string().as_str()).unwrap());
+}
+
+#[test]
+fn test_epd_clocks() {
+    init();
+    let epd = Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - id \"x\";").unwrap();
+    assert_eq!((0, 1), (epd.board.halfmove, epd.board.fullmove));
+
+    let epd = Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - hmvc 12; fmvn 40;").unwrap();
+    assert_eq!((12, 40), (epd.board.halfmove, epd.board.fullmove));
+    assert_eq!(
+        vec![EpdOp::HalfmoveClock(12), EpdOp::FullmoveNumber(40)],
+        epd.ops
+    );
+    assert_eq!(epd, Epd::try_from(epd.to_string().as_str()).unwrap());
+
+    assert_eq!(
+        EpdError::InvalidOperand {
+            opcode: "hmvc".to_string(),
+            operand: "-1".to_string()
+        },
+        Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - hmvc -1;").unwrap_err()
+    );
+    assert_eq!(
+        EpdError::InvalidOperand {
+            opcode: "fmvn".to_string(),
+            operand: "0".to_string()
+        },
+        Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - fmvn 0;").unwrap_err()
+    );
+}
+
+#[test]
+fn test_epd_errors() {
+    init();
+    for (epd, err) in [
+        (
+            "8/8/8/8/8/8/8/4K3 w - -; id \"x\";",
+            EpdError::InvalidFen(FenError::InvalidEnPassant {
+                column: 22,
+                value: "-;".to_string(),
+            }),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 1x 2;",
+            EpdError::InvalidOpcode("1x".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - acd deep;",
+            EpdError::InvalidOperand {
+                opcode: "acd".to_string(),
+                operand: "deep".to_string(),
+            },
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - id;",
+            EpdError::InvalidOperand {
+                opcode: "id".to_string(),
+                operand: "".to_string(),
+            },
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - bm Kd8;",
+            EpdError::InvalidMove(MoveParseError::Illegal("Kd8".to_string())),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - c0 \"open;",
+            EpdError::UnterminatedString,
+        ),
+    ] {
+        assert_eq!(Err(err), Epd::try_from(epd), "{epd}");
+    }
+}
+
+#[test]
+fn test_epd_suite() {
+    init();
+    // plays mate in one where there is one
+    let mut choose_move = |board: &Board| -> ChessMove {
+        let moves = board.generate_legal_moves();
+        moves
+            .iter()
+            .find(|m| matches!(m.board.get_game_state(), GameState::Finished(_)))
+            .copied()
+            .unwrap_or(moves[0])
+    };
+    let report = run_epd_suite(SUITE, 3, Some(&mut choose_move));
+
+    let lines: Vec<_> = report.cases.iter().map(|case| case.line).collect();
+    assert_eq!(vec![2, 3, 4, 6, 7], lines);
+    assert_eq!((3, 2), (report.passed(), report.failed()));
+
+    // D5 is deeper than allowed
+    assert_eq!(3, report.cases[0].checks.as_ref().unwrap().len());
+    assert_eq!(2, report.cases[2].checks.as_ref().unwrap().len());
+    assert!(report.cases[4].checks.is_err());
+    assert_eq!(
+        "PASS line 2 (start)\n\
+         PASS line 3 (kiwipete)\n\
+         PASS line 4 (fool)\n\
+         FAIL line 6 (wrong count); D1 expected 6, found 5\n\
+         FAIL line 7; malformed move 'Ke9'\n\
+         3/5 passed",
+        report.to_string()
+    );
+
+    // without a way to choose moves only perft is checked
+    let report = run_epd_suite(SUITE, 2, None);
+    assert_eq!(0, report.cases[2].checks.as_ref().unwrap().len());
+}
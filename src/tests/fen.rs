@@ -0,0 +1,195 @@
+use crate::board::Board;
+use crate::utils::extract_squares;
+use crate::utils::fen::*;
+use crate::utils::squares::*;
+use crate::utils::Colour;
+use crate::Square;
+
+#[test]
+fn test_fen_constants() {
+    for fen in [
+        START_POSITION,
+        CASTLE_KINGSIDE_POSITION,
+        CASTLE_QUEENSIDE_POSITION,
+        PROMOTION_POSITION,
+        CHECK_POSITION,
+        TRICKY_POSITION,
+        KILLER_POSITION,
+        CMK_POSITION,
+    ] {
+        assert!(fen.parse::<Board>().is_ok(), "failed to parse {fen}");
+    }
+}
+
+#[test]
+fn test_fen_fields() {
+    let board = Board::try_from(KILLER_POSITION).unwrap();
+    assert_eq!(vec![e6], extract_squares(1 << board.killer_square));
+
+    let board = Board::try_from(CMK_POSITION).unwrap();
+    assert_eq!(vec![g8], extract_squares(board.base.black.king));
+    assert_eq!((0, 9), (board.halfmove, board.fullmove));
+
+    // missing clocks default to "0 1"
+    let board = Board::try_from("4k3/8/8/8/8/8/8/4K3 b -  -").unwrap();
+    assert_eq!((0, 1), (board.halfmove, board.fullmove));
+}
+
+#[test]
+fn test_fen_errors() {
+    assert_eq!(
+        Board::try_from("").unwrap_err(),
+        FenError::MissingField(FenField::PiecePlacement)
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8 w - - 0 1").unwrap_err(),
+        FenError::InvalidRankCount(7)
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/7 w - - 0 1").unwrap_err(),
+        FenError::InvalidRankLength {
+            rank: 1,
+            column: 15
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/44p w - - 0 1").unwrap_err(),
+        FenError::InvalidRankLength {
+            rank: 1,
+            column: 16
+        }
+    );
+    assert_eq!(
+        Board::try_from("rnbqkbnr/ppppxppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap_err(),
+        FenError::InvalidPiece {
+            rank: 7,
+            column: 13,
+            symbol: 'x'
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8").unwrap_err(),
+        FenError::MissingField(FenField::SideToMove)
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 x - - 0 1").unwrap_err(),
+        FenError::InvalidSideToMove {
+            column: 16,
+            value: "x".to_string()
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 w KK - 0 1").unwrap_err(),
+        FenError::InvalidCastlingRights {
+            column: 18,
+            value: "KK".to_string()
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 w - e4 0 1").unwrap_err(),
+        FenError::InvalidEnPassant {
+            column: 20,
+            value: "e4".to_string()
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 w - - x 1").unwrap_err(),
+        FenError::InvalidHalfmoveClock {
+            column: 22,
+            value: "x".to_string()
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 w - - 0 -1").unwrap_err(),
+        FenError::InvalidFullmoveNumber {
+            column: 24,
+            value: "-1".to_string()
+        }
+    );
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/8 w - - 0 0").unwrap_err(),
+        FenError::InvalidFullmoveNumber {
+            column: 24,
+            value: "0".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_fen_position_errors() {
+    assert_eq!(
+        Board::try_from("8/8/8/8/8/8/8/4K3 w - - 0 1").unwrap_err(),
+        FenError::InvalidKingCount {
+            colour: Colour::Black,
+            count: 0
+        }
+    );
+    assert_eq!(
+        Board::try_from("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").unwrap_err(),
+        FenError::InvalidKingCount {
+            colour: Colour::White,
+            count: 2
+        }
+    );
+    assert_eq!(
+        Board::try_from("4k3/8/8/8/8/8/8/P3K3 w - - 0 1").unwrap_err(),
+        FenError::PawnOnBackRank(Square::A1)
+    );
+    // the king has left its starting square, so it can't castle with the rook
+    assert_eq!(
+        Board::try_from("4k3/8/8/8/8/8/8/3K3R w K - 0 1").unwrap_err(),
+        FenError::UnavailableCastlingRight {
+            column: 23,
+            right: 'K'
+        }
+    );
+    assert_eq!(
+        Board::try_from("4k2r/8/8/8/8/8/8/4K3 w q - 0 1").unwrap_err(),
+        FenError::UnavailableCastlingRight {
+            column: 23,
+            right: 'q'
+        }
+    );
+    // en passant squares must be behind a pawn of the side that just moved
+    for fen in [
+        "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1",
+        "4k3/8/8/4p3/8/8/8/4K3 w - d6 0 1",
+        "4k3/8/8/4p3/8/8/8/4K3 b - e6 0 1",
+    ] {
+        assert!(
+            matches!(
+                Board::try_from(fen).unwrap_err(),
+                FenError::InvalidEnPassant { column: 26, .. }
+            ),
+            "{fen}"
+        );
+    }
+    assert!(Board::try_from("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").is_ok());
+    assert_eq!(
+        Board::try_from("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1").unwrap_err(),
+        FenError::OpponentInCheck
+    );
+    assert!(Board::try_from("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1").is_ok());
+}
+
+#[test]
+fn test_fen_round_trip() {
+    for fen in [
+        START_POSITION,
+        CASTLE_KINGSIDE_POSITION,
+        CASTLE_QUEENSIDE_POSITION,
+        PROMOTION_POSITION,
+        CHECK_POSITION,
+        TRICKY_POSITION,
+        KILLER_POSITION,
+        CMK_POSITION,
+    ] {
+        let board = Board::try_from(fen).unwrap();
+        assert_eq!(fen.trim_end(), board.to_fen());
+        assert_eq!(fen.trim_end(), Fen(&board).to_string());
+    }
+
+    // the clocks are always written, even if they were left out
+    let board = Board::try_from("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+    assert_eq!("4k3/8/8/8/8/8/8/4K3 w - - 0 1", board.to_fen());
+}
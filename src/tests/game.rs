@@ -0,0 +1,125 @@
+use super::{find_move, init};
+use crate::board::{Board, FinishedState, GameState, ReasonDraw, ReasonWin};
+use crate::game::{Game, GameError, Position};
+use crate::utils::squares::*;
+use crate::utils::Colour;
+
+#[test]
+fn test_repetition() {
+    init();
+    let mut game = Game::default();
+    for i in 0..16 {
+        if i == 8 {
+            // the starting position has now occured three times
+            assert_eq!(3, game.repetitions());
+            assert_eq!(Some(ReasonDraw::Repetition), game.claimable_draw());
+        } else if i < 8 {
+            assert!(game.claimable_draw().is_none());
+        }
+        let (from, to) = [(g1, f3), (g8, f6), (f3, g1), (f6, g8)][i % 4];
+        let m = find_move(game.board(), from, to, None);
+        game.play(m).unwrap();
+    }
+
+    assert_eq!(5, game.repetitions());
+    assert!(matches!(
+        game.get_game_state(),
+        GameState::Finished(FinishedState::Draw(ReasonDraw::Repetition))
+    ));
+    let m = find_move(game.board(), g1, f3, None);
+    assert!(game.play(m).is_err());
+}
+
+#[test]
+fn test_position_en_passant() {
+    init();
+    // the en passant square only matters if the capture is possible
+    let with = Board::try_from("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+    let without = Board::try_from("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(Position::from(&with), Position::from(&without));
+
+    let with = Board::try_from("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let without = Board::try_from("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(Position::from(&with), Position::from(&without));
+
+    // castling rights are part of the position
+    let with = Board::try_from("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let without = Board::try_from("4k3/8/8/8/8/8/8/4K2R w - - 0 1").unwrap();
+    assert_ne!(Position::from(&with), Position::from(&without));
+}
+
+#[test]
+fn test_resignation() {
+    init();
+    let mut game = Game::default();
+    assert_eq!(
+        Ok(FinishedState::Win(Colour::White, ReasonWin::Resignation)),
+        game.resign(Colour::Black)
+    );
+    assert!(matches!(
+        game.get_game_state(),
+        GameState::Finished(FinishedState::Win(Colour::White, ReasonWin::Resignation))
+    ));
+    assert_eq!(Err(GameError::GameFinished), game.resign(Colour::White));
+    let m = find_move(game.board(), e2, e4, None);
+    assert_eq!(Err(GameError::GameFinished), game.play(m));
+}
+
+#[test]
+fn test_draw_offers() {
+    init();
+    let mut game = Game::default();
+    assert_eq!(Err(GameError::NoDrawOffer), game.accept_draw(Colour::Black));
+
+    game.offer_draw(Colour::White).unwrap();
+    assert_eq!(Some(Colour::White), game.draw_offer());
+    assert_eq!(
+        Err(GameError::DrawAlreadyOffered),
+        game.offer_draw(Colour::Black)
+    );
+    // the offering side cannot accept its own offer
+    assert_eq!(Err(GameError::NoDrawOffer), game.accept_draw(Colour::White));
+    game.decline_draw(Colour::Black).unwrap();
+    assert_eq!(None, game.draw_offer());
+
+    // the offer stays open when the offering side moves, but lapses when the opponent moves
+    game.offer_draw(Colour::White).unwrap();
+    game.play(find_move(game.board(), e2, e4, None)).unwrap();
+    assert_eq!(Some(Colour::White), game.draw_offer());
+    game.play(find_move(game.board(), e7, e5, None)).unwrap();
+    assert_eq!(None, game.draw_offer());
+
+    game.offer_draw(Colour::Black).unwrap();
+    assert_eq!(
+        Ok(FinishedState::Draw(ReasonDraw::Agreement)),
+        game.accept_draw(Colour::White)
+    );
+    assert!(matches!(
+        game.get_game_state(),
+        GameState::Finished(FinishedState::Draw(ReasonDraw::Agreement))
+    ));
+}
+
+#[test]
+fn test_draw_claims() {
+    init();
+    let mut game = Game::default();
+    assert_eq!(
+        Err(GameError::NoDrawToClaim),
+        game.claim_draw(Colour::White)
+    );
+    for (from, to) in [(g1, f3), (g8, f6), (f3, g1), (f6, g8)].repeat(2) {
+        game.play(find_move(game.board(), from, to, None)).unwrap();
+    }
+    assert_eq!(Err(GameError::NotYourTurn), game.claim_draw(Colour::Black));
+    assert_eq!(
+        Ok(FinishedState::Draw(ReasonDraw::Repetition)),
+        game.claim_draw(Colour::White)
+    );
+
+    let mut game = Game::from(Board::try_from("4k3/8/8/8/8/8/4P3/4K3 b - - 100 80").unwrap());
+    assert_eq!(
+        Ok(FinishedState::Draw(ReasonDraw::HalfmoveLimit)),
+        game.claim_draw(Colour::Black)
+    );
+}
@@ -0,0 +1,83 @@
+use super::init;
+use crate::board::{Board, FinishedState, GameState, ReasonDraw, ReasonWin};
+use crate::utils::Colour;
+use crate::START_POSITION;
+
+#[test]
+fn test_halfmove_reset() {
+    init();
+    let board = Board::try_from(START_POSITION).unwrap();
+    for m in board.generate_legal_moves() {
+        if m.board.base.white.pawns != board.base.white.pawns {
+            assert_eq!(0, m.board.halfmove);
+        } else {
+            assert_eq!(1, m.board.halfmove);
+        }
+    }
+
+    let board = Board::try_from("4k3/8/8/3p4/8/8/8/3RK3 w - - 10 20").unwrap();
+    for m in board.generate_legal_moves() {
+        if m.board.base.black.pawns == 0 {
+            assert_eq!(0, m.board.halfmove);
+        } else {
+            assert_eq!(11, m.board.halfmove);
+        }
+    }
+}
+
+#[test]
+fn test_halfmove_limit() {
+    init();
+    let board = Board::try_from("4k3/8/8/8/8/8/4P3/4K3 w - - 99 80").unwrap();
+    assert!(board.claimable_draw().is_none());
+    assert!(matches!(board.get_game_state(), GameState::Ongoing(_)));
+
+    let board = Board::try_from("4k3/8/8/8/8/8/4P3/4K3 w - - 100 80").unwrap();
+    assert_eq!(Some(ReasonDraw::HalfmoveLimit), board.claimable_draw());
+    assert!(matches!(board.get_game_state(), GameState::Ongoing(_)));
+
+    let board = Board::try_from("4k3/8/8/8/8/8/4P3/4K3 w - - 150 100").unwrap();
+    assert!(matches!(
+        board.get_game_state(),
+        GameState::Finished(FinishedState::Draw(ReasonDraw::HalfmoveLimit))
+    ));
+
+    // checkmate on the last move takes precedence over the 75 move rule
+    let board = Board::try_from("R5k1/5ppp/8/8/8/8/8/6K1 b - - 150 100").unwrap();
+    assert!(matches!(
+        board.get_game_state(),
+        GameState::Finished(FinishedState::Win(Colour::White, ReasonWin::Checkmate))
+    ));
+}
+
+#[test]
+fn test_insufficient_material() {
+    init();
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4Kb2 w - - 0 1",
+        // bishops on same coloured squares
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/B3K1B1 w - - 0 1",
+    ] {
+        let board = Board::try_from(fen).unwrap();
+        assert!(matches!(
+            board.get_game_state(),
+            GameState::Finished(FinishedState::Draw(ReasonDraw::InsufficientMaterial))
+        ));
+    }
+
+    for fen in [
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2KB2 w - - 0 1",
+        "4kn2/8/8/8/8/8/8/4KN2 w - - 0 1",
+        // bishops on opposite coloured squares
+        "4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ] {
+        let board = Board::try_from(fen).unwrap();
+        assert!(matches!(board.get_game_state(), GameState::Ongoing(_)));
+    }
+}
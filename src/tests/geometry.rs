@@ -0,0 +1,93 @@
+use crate::{
+    aligned, between, distance, line, manhattan_distance, ray, Bitboard, Direction, Square,
+};
+
+fn squares(squares: &[Square]) -> Bitboard {
+    squares.iter().copied().collect()
+}
+
+#[test]
+fn test_between() {
+    assert_eq!(
+        squares(&[Square::F1, Square::G1]),
+        between(Square::E1, Square::H1)
+    );
+    assert_eq!(
+        squares(&[Square::B1, Square::C1, Square::D1]),
+        between(Square::E1, Square::A1)
+    );
+    assert_eq!(
+        squares(&[Square::C3, Square::D4, Square::E5]),
+        between(Square::F6, Square::B2)
+    );
+    assert_eq!(Bitboard::EMPTY, between(Square::E1, Square::F1));
+    assert_eq!(Bitboard::EMPTY, between(Square::A1, Square::B3));
+    assert_eq!(Bitboard::EMPTY, between(Square::D4, Square::D4));
+}
+
+#[test]
+fn test_line() {
+    assert_eq!(Bitboard::FILES[4], line(Square::E2, Square::E7));
+    assert_eq!(Bitboard::RANKS[0], line(Square::H1, Square::C1));
+    assert_eq!(Bitboard::DIAGONALS[7], line(Square::C3, Square::F6));
+    assert_eq!(Bitboard::ANTI_DIAGONALS[7], line(Square::B7, Square::G2));
+    assert_eq!(Bitboard::EMPTY, line(Square::A1, Square::B3));
+    assert_eq!(Bitboard::EMPTY, line(Square::D4, Square::D4));
+}
+
+#[test]
+fn test_tables_agree() {
+    for a in Square::ALL {
+        for b in Square::ALL {
+            assert_eq!(between(a, b), between(b, a));
+            assert_eq!(line(a, b), line(b, a));
+            assert_eq!(Bitboard::EMPTY, between(a, b) & !line(a, b), "{a} {b}");
+            if !line(a, b).is_empty() {
+                assert_eq!(distance(a, b), between(a, b).count() + 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ray() {
+    assert_eq!(
+        squares(&[Square::E5, Square::E6, Square::E7, Square::E8]),
+        ray(Square::E4, Direction::North)
+    );
+    assert_eq!(
+        squares(&[Square::C2, Square::B1]),
+        ray(Square::D3, Direction::SouthWest)
+    );
+    assert_eq!(Bitboard::EMPTY, ray(Square::H4, Direction::NorthEast));
+    for square in Square::ALL {
+        let all = Direction::ALL
+            .into_iter()
+            .fold(Bitboard::EMPTY, |res, direction| {
+                res | ray(square, direction)
+            });
+        let lines = Square::ALL
+            .into_iter()
+            .fold(Bitboard::EMPTY, |res, other| res | line(square, other));
+        assert_eq!(lines & !square.bitboard(), all);
+    }
+}
+
+#[test]
+fn test_aligned() {
+    assert!(aligned(Square::A1, Square::D4, Square::H8));
+    assert!(aligned(Square::E1, Square::E8, Square::E4));
+    assert!(aligned(Square::B2, Square::G2, Square::A2));
+    assert!(!aligned(Square::A1, Square::D4, Square::D5));
+    assert!(!aligned(Square::A1, Square::B3, Square::C5));
+}
+
+#[test]
+fn test_distance() {
+    assert_eq!(0, distance(Square::D4, Square::D4));
+    assert_eq!(7, distance(Square::A1, Square::H8));
+    assert_eq!(3, distance(Square::E1, Square::G4));
+    assert_eq!(14, manhattan_distance(Square::A1, Square::H8));
+    assert_eq!(5, manhattan_distance(Square::E1, Square::G4));
+    assert_eq!(1, manhattan_distance(Square::H2, Square::H1));
+}
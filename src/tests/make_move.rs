@@ -0,0 +1,27 @@
+use super::for_each_position;
+use crate::board::Board;
+
+/// Plays every move `depth` plies deep in place, checking each against the boards the moves carry.
+fn check_make_unmake(board: &mut Board, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+    let before = *board;
+    for chess_move in board.generate_legal_moves() {
+        let undo = board.make_move(&chess_move);
+        assert_eq!(
+            chess_move.board,
+            *board,
+            "{chess_move} in {}",
+            before.to_fen()
+        );
+        check_make_unmake(board, depth - 1);
+        board.unmake_move(&chess_move, undo);
+        assert_eq!(before, *board, "{chess_move} in {}", before.to_fen());
+    }
+}
+
+#[test]
+fn test_make_unmake() {
+    for_each_position(check_make_unmake);
+}
@@ -1,2 +1,97 @@
 #[cfg(test)]
+use crate::{
+    board::{Board, ChessMove},
+    utils::{fen::*, Piece},
+};
+
+#[cfg(test)]
+mod bitboard;
+#[cfg(test)]
+mod chess_move;
+#[cfg(test)]
+mod epd;
+#[cfg(test)]
+mod fen;
+#[cfg(test)]
+mod game;
+#[cfg(test)]
+mod game_state;
+#[cfg(test)]
+mod geometry;
+#[cfg(test)]
+mod make_move;
+#[cfg(test)]
+mod moves;
+#[cfg(test)]
+mod perft;
+#[cfg(test)]
+mod pgn;
+#[cfg(test)]
+mod polyglot;
+#[cfg(test)]
+mod san;
+#[cfg(test)]
+mod sliders;
+#[cfg(test)]
 mod squares;
+#[cfg(test)]
+mod uci;
+#[cfg(test)]
+mod zobrist;
+
+/// Fills the attack tables for tests that use them without a board.
+#[cfg(test)]
+fn init() {
+    crate::init();
+}
+
+/// Finds the legal move taking the piece on `from` to `to`, promoting to `promotion`.
+#[cfg(test)]
+fn find_move(board: &Board, from: i32, to: i32, promotion: Option<Piece>) -> ChessMove {
+    board
+        .generate_legal_moves()
+        .into_iter()
+        .find(|m| {
+            m.source_square() as i32 == from
+                && m.destination_square() as i32 == to
+                && m.promotion() == promotion
+        })
+        .unwrap()
+}
+
+/// En passant that would expose the king along the rank.
+#[cfg(test)]
+const EN_PASSANT_PIN_POSITION: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+/// Promotions with captures, and castling rights lost to captured rooks.
+#[cfg(test)]
+const PROMOTION_CAPTURE_POSITION: &str =
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+/// Promotions next to the player's own pieces.
+#[cfg(test)]
+const PROMOTION_BLOCKED_POSITION: &str = "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1";
+
+/// Positions between them covering every kind of move, each with a depth that keeps walking
+/// its move tree quick.
+#[cfg(test)]
+const POSITIONS: [(&str, u32); 11] = [
+    (START_POSITION, 3),
+    (TRICKY_POSITION, 2),
+    (KILLER_POSITION, 2),
+    (CHECK_POSITION, 3),
+    (CMK_POSITION, 2),
+    (PROMOTION_POSITION, 3),
+    (CASTLE_KINGSIDE_POSITION, 2),
+    (CASTLE_QUEENSIDE_POSITION, 2),
+    (PROMOTION_CAPTURE_POSITION, 2),
+    (PROMOTION_BLOCKED_POSITION, 3),
+    (EN_PASSANT_PIN_POSITION, 3),
+];
+
+/// Runs `check` on a board of each of `POSITIONS` with its depth.
+#[cfg(test)]
+fn for_each_position(mut check: impl FnMut(&mut Board, u32)) {
+    init();
+    for (fen, depth) in POSITIONS {
+        check(&mut Board::try_from(fen).unwrap(), depth);
+    }
+}
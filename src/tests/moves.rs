@@ -0,0 +1,129 @@
+use super::{for_each_position, init};
+use crate::board::Board;
+use crate::moves::{Move, MoveList};
+use crate::utils::fen::*;
+use crate::Square;
+use std::collections::HashSet;
+
+/// Compares the compact moves with the `ChessMove`s and with the pseudo-legal moves that pass
+/// `is_legal`, `depth` plies deep, playing them in place.
+fn check_moves(board: &mut Board, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+    let mut list = MoveList::new();
+    board.generate_moves(&mut list);
+    let expected: HashSet<Move> = board
+        .generate_legal_moves()
+        .iter()
+        .map(Move::from)
+        .collect();
+    assert_eq!(list.len(), expected.len(), "{}", board.to_fen());
+    assert_eq!(list.iter().copied().collect::<HashSet<_>>(), expected);
+    let mut pseudo_legal = MoveList::new();
+    board.generate_pseudo_legal_moves(&mut pseudo_legal);
+    let filtered: HashSet<Move> = pseudo_legal
+        .iter()
+        .copied()
+        .filter(|&m| board.is_legal(m))
+        .collect();
+    assert_eq!(filtered, expected, "{}", board.to_fen());
+
+    let before = *board;
+    for &chess_move in &list {
+        let undo = board.make(chess_move).unwrap();
+        assert_eq!(
+            before.chess_move(chess_move).unwrap().board,
+            *board,
+            "{chess_move} in {}",
+            before.to_fen()
+        );
+        check_moves(board, depth - 1);
+        assert!(board.unmake(chess_move, undo));
+        assert_eq!(before, *board, "{chess_move} in {}", before.to_fen());
+    }
+}
+
+#[test]
+fn test_generate_moves() {
+    for_each_position(check_moves);
+}
+
+#[test]
+fn test_generate_moves_checks_and_pins() {
+    init();
+    let mut list = MoveList::new();
+    let moves = |fen: &str, list: &mut MoveList| {
+        Board::try_from(fen).unwrap().generate_moves(list);
+        list.iter().map(Move::to_string).collect::<HashSet<_>>()
+    };
+
+    // en passant would take both pawns off the rank the king is attacked along
+    let res = moves("8/8/8/KPp4r/8/8/8/7k w - c6 0 1", &mut list);
+    assert!(!res.contains("b5c6"));
+    // en passant captures the pawn giving check
+    let res = moves("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1", &mut list);
+    assert!(res.contains("e4d3"));
+    // a pinned pawn may capture en passant along the pin
+    let res = moves("8/8/2k5/8/4pP2/8/8/4K2B b - f3 0 1", &mut list);
+    assert!(res.contains("e4f3"));
+    // in double check only the king moves
+    let res = moves("4r2k/8/8/8/8/3n4/3Q4/4K3 w - - 0 1", &mut list);
+    assert_eq!(res, HashSet::from(["e1d1".to_string(), "e1f1".to_string()]));
+    // a pinned rook moves along the pin only
+    let res = moves("4k3/4r3/8/8/8/8/4R3/4K3 b - - 0 1", &mut list);
+    assert!(res.contains("e7e2"));
+    assert!(res
+        .iter()
+        .filter(|m| m.starts_with("e7"))
+        .all(|m| m.as_bytes()[2] == b'e'));
+}
+
+#[test]
+fn test_move_encoding() {
+    init();
+    let board = Board::try_from("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap();
+    let mut list = MoveList::new();
+    board.generate_moves(&mut list);
+    let capture = list
+        .iter()
+        .find(|m| m.to_string() == "g2h1n")
+        .expect("g2h1n is legal");
+    assert_eq!(capture.source_square(), Square::G2);
+    assert_eq!(capture.destination_square(), Square::H1);
+    assert!(capture.is_capture());
+    assert_eq!(capture.castling(), None);
+    assert_eq!(
+        board.chess_move(*capture).unwrap().to_string(),
+        capture.to_string()
+    );
+
+    let board = Board::try_from(CASTLE_KINGSIDE_POSITION).unwrap();
+    board.generate_moves(&mut list);
+    assert!(list.iter().any(|m| m.castling().is_some()));
+    assert_eq!(std::mem::size_of::<Move>(), 2);
+}
+
+/// Moves kept from another position are turned down instead of corrupting the board.
+#[test]
+fn test_stale_moves() {
+    init();
+    let mut board = Board::try_from(START_POSITION).unwrap();
+    let mut list = MoveList::new();
+    board.generate_moves(&mut list);
+    let e2e4 = *list.iter().find(|m| m.to_string() == "e2e4").unwrap();
+    let g1f3 = *list.iter().find(|m| m.to_string() == "g1f3").unwrap();
+
+    let start = board;
+    let undo = board.make(e2e4).unwrap();
+    let after = board;
+    assert_eq!(None, board.make(e2e4));
+    assert_eq!(None, board.make(g1f3));
+    assert!(!board.is_legal(e2e4));
+    assert_eq!(after, board);
+
+    assert!(!board.unmake(g1f3, undo));
+    assert_eq!(after, board);
+    assert!(board.unmake(e2e4, undo));
+    assert_eq!(start, board);
+}
@@ -0,0 +1,126 @@
+use super::{
+    init, EN_PASSANT_PIN_POSITION, PROMOTION_BLOCKED_POSITION, PROMOTION_CAPTURE_POSITION,
+};
+use crate::board::Board;
+use crate::perft::PerftStats;
+use crate::utils::fen::*;
+
+fn stats(
+    nodes: u64,
+    captures: u64,
+    en_passants: u64,
+    castles: u64,
+    promotions: u64,
+    checks: u64,
+    checkmates: u64,
+) -> PerftStats {
+    PerftStats {
+        nodes,
+        captures,
+        en_passants,
+        castles,
+        promotions,
+        checks,
+        checkmates,
+    }
+}
+
+#[test]
+fn test_perft_start_position() {
+    init();
+    let board = Board::try_from(START_POSITION).unwrap();
+    assert_eq!(stats(20, 0, 0, 0, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(400, 0, 0, 0, 0, 0, 0), board.perft(2));
+    assert_eq!(stats(8902, 34, 0, 0, 0, 12, 0), board.perft(3));
+}
+
+#[test]
+fn test_perft_tricky_position() {
+    init();
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    assert_eq!(stats(48, 8, 0, 2, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(2039, 351, 1, 91, 0, 3, 0), board.perft(2));
+    assert_eq!(stats(97862, 17102, 45, 3162, 0, 993, 1), board.perft(3));
+}
+
+#[test]
+fn test_perft_killer_position() {
+    init();
+    let board = Board::try_from(KILLER_POSITION).unwrap();
+    assert_eq!(stats(42, 12, 1, 0, 12, 2, 0), board.perft(1));
+    assert_eq!(stats(1088, 159, 0, 0, 0, 40, 2), board.perft(2));
+    assert_eq!(stats(39518, 9052, 6, 0, 8620, 1992, 1), board.perft(3));
+}
+
+#[test]
+fn test_perft_cmk_position() {
+    init();
+    let board = Board::try_from(CMK_POSITION).unwrap();
+    assert_eq!(stats(43, 3, 0, 0, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(1289, 145, 0, 0, 0, 0, 0), board.perft(2));
+    assert_eq!(stats(54240, 4490, 42, 0, 0, 41, 0), board.perft(3));
+}
+
+#[test]
+fn test_perft_promotion_position() {
+    init();
+    let board = Board::try_from(PROMOTION_POSITION).unwrap();
+    assert_eq!(stats(10, 0, 0, 0, 4, 2, 0), board.perft(1));
+    assert_eq!(stats(36, 0, 0, 0, 0, 0, 0), board.perft(2));
+    assert_eq!(stats(466, 0, 0, 0, 112, 60, 0), board.perft(3));
+    assert_eq!(stats(2489, 28, 0, 0, 0, 0, 0), board.perft(4));
+
+    let board = Board::try_from(CHECK_POSITION).unwrap();
+    assert_eq!(stats(5, 1, 0, 0, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(32, 0, 0, 0, 12, 5, 0), board.perft(2));
+    assert_eq!(stats(305, 12, 0, 0, 108, 39, 0), board.perft(3));
+}
+
+#[test]
+fn test_perft_castling_positions() {
+    init();
+    let board = Board::try_from(CASTLE_KINGSIDE_POSITION).unwrap();
+    assert_eq!(stats(22, 0, 0, 1, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(484, 0, 0, 22, 0, 0, 0), board.perft(2));
+    assert_eq!(stats(11202, 23, 0, 396, 0, 8, 0), board.perft(3));
+
+    let board = Board::try_from(CASTLE_QUEENSIDE_POSITION).unwrap();
+    assert_eq!(stats(23, 0, 0, 1, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(529, 0, 0, 23, 0, 0, 0), board.perft(2));
+    assert_eq!(stats(12263, 21, 0, 414, 0, 4, 0), board.perft(3));
+}
+
+#[test]
+fn test_perft_edge_cases() {
+    init();
+    let board = Board::try_from(EN_PASSANT_PIN_POSITION).unwrap();
+    assert_eq!(stats(14, 1, 0, 0, 0, 2, 0), board.perft(1));
+    assert_eq!(stats(191, 14, 0, 0, 0, 10, 0), board.perft(2));
+    assert_eq!(stats(2812, 209, 2, 0, 0, 267, 0), board.perft(3));
+    assert_eq!(stats(43238, 3348, 123, 0, 0, 1680, 17), board.perft(4));
+
+    let board = Board::try_from(PROMOTION_CAPTURE_POSITION).unwrap();
+    assert_eq!(stats(6, 0, 0, 0, 0, 0, 0), board.perft(1));
+    assert_eq!(stats(264, 87, 0, 6, 48, 10, 0), board.perft(2));
+    assert_eq!(stats(9467, 1021, 4, 0, 120, 38, 22), board.perft(3));
+
+    let board = Board::try_from(PROMOTION_BLOCKED_POSITION).unwrap();
+    assert_eq!(24, board.perft(1).nodes);
+    assert_eq!(496, board.perft(2).nodes);
+    assert_eq!(9483, board.perft(3).nodes);
+}
+
+#[test]
+fn test_divide() {
+    init();
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    let divide = board.divide(2);
+    assert_eq!(48, divide.len());
+
+    let mut total = PerftStats::default();
+    for (_, stats) in divide {
+        total += stats;
+    }
+    assert_eq!(board.perft(2), total);
+    assert_eq!(stats(1, 0, 0, 0, 0, 0, 0), board.perft(0));
+}
@@ -0,0 +1,193 @@
+use super::init;
+use crate::board::{Board, GameState};
+use crate::game::Game;
+use crate::pgn::{read_games, PgnError, PgnGame, PgnResult};
+use crate::Square;
+use std::time::Duration;
+
+const GAMES: &str = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+[Annotator "Someone \"quoted\""]
+
+{Opening comment} 1. e4 e5 2. Nf3 Nc6 3. Bb5 {This opening is called the Ruy Lopez.}
+3... a6 $1 4. Ba4 (4. Bxc6 dxc6 (4... bxc6?! 5. O-O) 5. O-O) 4... Nf6 5. O-O Be7 ; rest of line
+6. Re1 b5!? 7. Bb3 d6 8. c3 O-O 1/2-1/2
+
+[Event "Broken"]
+[Result "*"]
+
+1. e4 e5 2. Ke3 *
+
+% an escaped line that is ignored
+[Event "From position"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K2R b K - 0 30"]
+[Result "0-1"]
+
+30... Kd7 31.O-O Kc6 0-1
+"#;
+
+#[test]
+fn test_pgn_read() {
+    init();
+    let games: Vec<_> = read_games(GAMES).collect();
+    assert_eq!(3, games.len());
+
+    // --- Game 1 ---
+    let game = games[0].as_ref().unwrap();
+    assert_eq!(8, game.tags.len());
+    assert_eq!(Some("Belgrade, Serbia JUG"), game.tag("Site"));
+    assert_eq!(Some("Someone \"quoted\""), game.tag("Annotator"));
+    assert_eq!(PgnResult::Draw, game.result);
+    assert_eq!(16, game.moves.len());
+    assert_eq!(vec!["Opening comment"], game.moves[0].comments_before);
+    assert_eq!(
+        vec!["This opening is called the Ruy Lopez."],
+        game.moves[4].comments
+    );
+    assert_eq!(vec![1], game.moves[5].nags);
+    assert_eq!(vec![5], game.moves[11].nags);
+    assert_eq!(vec!["rest of line"], game.moves[9].comments);
+
+    // 4. Ba4 has the alternative 4. Bxc6, which itself has the alternative 4... bxc6
+    let variation = &game.moves[6].variations[0];
+    assert_eq!(3, variation.len());
+    assert_eq!(Square::B5, variation[0].chess_move.source_square());
+    assert_eq!(Square::C6, variation[0].chess_move.destination_square());
+    let nested = &variation[1].variations[0];
+    assert_eq!(2, nested.len());
+    assert_eq!(Square::B7, nested[0].chess_move.source_square());
+    assert_eq!(vec![6], nested[0].nags);
+
+    let replayed = game.game().unwrap();
+    assert_eq!(16, replayed.moves().len());
+    assert!(matches!(replayed.get_game_state(), GameState::Ongoing(_)));
+
+    // --- Game 2 ---
+    // the illegal move only spoils its own game
+    assert!(matches!(games[1], Err(PgnError::InvalidMove(_))));
+
+    // --- Game 3 ---
+    let game = games[2].as_ref().unwrap();
+    assert_eq!(
+        Board::try_from("4k3/8/8/8/8/8/4P3/4K2R b K - 0 30").unwrap(),
+        game.start
+    );
+    assert_eq!(3, game.moves.len());
+    assert!(game.moves[1].chess_move.castling().is_some());
+    assert_eq!(PgnResult::BlackWins, game.result);
+}
+
+#[test]
+fn test_pgn_errors() {
+    init();
+    for (pgn, check) in [
+        ("[Event \"x\"\n1. e4 *", "tag"),
+        ("1. e4 (1. d4 *", "variation"),
+        ("1. e4 ) *", "variation"),
+        ("( 1. e4 ) *", "variation"),
+        ("1. e4 {never closed *", "comment"),
+        ("1. e5 *", "move"),
+        ("[FEN \"8/8 w - -\"]\n *", "fen"),
+    ] {
+        let err = read_games(pgn).next().unwrap().unwrap_err();
+        let ok = match check {
+            "tag" => matches!(err, PgnError::InvalidTag(_)),
+            "variation" => matches!(err, PgnError::UnbalancedVariation),
+            "comment" => matches!(err, PgnError::UnterminatedComment),
+            "move" => matches!(err, PgnError::InvalidMove(_)),
+            _ => matches!(err, PgnError::InvalidFen(_)),
+        };
+        assert!(ok, "{pgn}: {err:?}");
+    }
+}
+
+#[test]
+fn test_pgn_oversized_clock() {
+    init();
+    let game = read_games("1. e4 {[%clk 9999999999999999999:00:00]} *")
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(None, game.moves[0].clock);
+    assert_eq!(
+        vec!["[%clk 9999999999999999999:00:00]"],
+        game.moves[0].comments
+    );
+}
+
+#[test]
+fn test_pgn_write() {
+    init();
+    let mut game = Game::default();
+    for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+        let chess_move = game.board().parse_uci_move(uci).unwrap();
+        game.play(chess_move).unwrap();
+    }
+    let mut pgn = PgnGame::from(&game);
+    pgn.set_tag("White", "Fool");
+    pgn.moves[0].clock = Some(Duration::from_secs(300));
+    pgn.moves[1].clock = Some(Duration::from_millis(299_500));
+    pgn.moves[1].nags.push(1);
+    assert_eq!(
+        "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+         [White \"Fool\"]\n[Black \"?\"]\n[Result \"0-1\"]\n\n\
+         1. f3 {[%clk 0:05:00]} 1... e5 $1 {[%clk 0:04:59.5]} 2. g4 Qh4# 0-1\n",
+        pgn.to_string()
+    );
+
+    // numbering continues from the fullmove number, starting with black
+    let start = Board::try_from("4k3/8/8/8/8/8/4P3/4K2R b K - 0 30").unwrap();
+    let mut game = Game::new(start);
+    for uci in ["e8d7", "e1g1"] {
+        let chess_move = game.board().parse_uci_move(uci).unwrap();
+        game.play(chess_move).unwrap();
+    }
+    let pgn = PgnGame::from(&game).to_string();
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K2R b K - 0 30\"]\n"));
+    assert!(pgn.ends_with("\n30... Kd7 31. O-O *\n"));
+}
+
+#[test]
+fn test_pgn_round_trip() {
+    init();
+    for game in read_games(GAMES).filter_map(Result::ok) {
+        let mut game = game;
+        game.moves[0]
+            .comments
+            .push("a long comment that will have to be wrapped over more than one line".repeat(2));
+        let written = game.to_string();
+        assert!(written.lines().all(|line| line.len() <= 80), "{written}");
+
+        let read = read_games(&written).next().unwrap().unwrap();
+        assert_eq!(game.start, read.start);
+        assert_eq!(game.moves, read.moves);
+        assert_eq!(game.result, read.result);
+        for (name, value) in &game.tags {
+            assert_eq!(Some(value.as_str()), read.tag(name));
+        }
+    }
+}
+
+#[test]
+fn test_pgn_round_trip_closing_brace() {
+    init();
+    let mut game = read_games(GAMES).next().unwrap().unwrap();
+    // the braces are dropped rather than ending the comments
+    let mut expected = game.moves.clone();
+    expected[0].comments.push("ends here 2. Qh5 *".to_string());
+    expected[1].comments.push(String::new());
+    game.moves[0]
+        .comments
+        .push("ends here } 2. Qh5 *".to_string());
+    game.moves[1].comments.push("}".to_string());
+
+    let read = read_games(&game.to_string()).next().unwrap().unwrap();
+    assert_eq!(expected, read.moves);
+    assert_eq!(game.result, read.result);
+}
@@ -0,0 +1,165 @@
+use super::init;
+use crate::board::{Board, Side};
+use crate::pgn::read_games;
+use crate::polyglot::{BookBuilder, BookEntry, BookError, BookOptions, PolyglotBook};
+use crate::utils::squares::*;
+use crate::Square;
+
+/// The keys published with the Polyglot book format.
+#[test]
+fn test_polyglot_keys() {
+    init();
+    let mut board = Board::default();
+    assert_eq!(0x463b96181691fc9c, board.polyglot_key());
+    for (uci, key) in [
+        ("e2e4", 0x823c9b50fd114196),
+        ("d7d5", 0x0756b94461c50fb0),
+        ("e4e5", 0x662fafb965db29d4),
+        ("f7f5", 0x22a48b5a8e47ff78),
+        ("e1e2", 0x652a607ca3f242c1),
+        ("e8f7", 0x00fdd303c946bdd9),
+    ] {
+        board = board.parse_uci_move(uci).unwrap().board;
+        assert_eq!(key, board.polyglot_key(), "after {uci}");
+    }
+
+    let mut board = Board::default();
+    for (uci, key) in [
+        ("a2a4", None),
+        ("b7b5", None),
+        ("h2h4", None),
+        ("b5b4", None),
+        // en passant is possible and hashed
+        ("c2c4", Some(0x3c8123ea7b067637)),
+        ("b4c3", None),
+        ("a1a3", Some(0x5c3f9b829b279560)),
+    ] {
+        board = board.parse_uci_move(uci).unwrap().board;
+        if let Some(key) = key {
+            assert_eq!(key, board.polyglot_key(), "after {uci}");
+        }
+    }
+}
+
+fn entry(board: &Board, source: i32, destination: i32, weight: u16) -> BookEntry {
+    BookEntry {
+        key: board.polyglot_key(),
+        raw_move: (source << 6 | destination) as u16,
+        weight,
+        learn: 0,
+    }
+}
+
+#[test]
+fn test_polyglot_book() {
+    init();
+    let start = Board::default();
+    let castle = Board::try_from("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+    let entries = vec![
+        entry(&castle, e8, a8, 5),
+        entry(&start, e2, e4, 10),
+        entry(&start, d2, d4, 30),
+        // not a legal move, so left out
+        entry(&start, e2, e5, 100),
+        entry(&start, g1, f3, 10),
+        entry(&castle, e8, h8, 1),
+    ];
+    let book = PolyglotBook::from_entries(entries.clone());
+    assert_eq!(4, book.lookup(start.polyglot_key()).len());
+    assert!(book.lookup(0).is_empty());
+
+    // the book survives being written out
+    let bytes = book.to_bytes();
+    assert_eq!(16 * entries.len(), bytes.len());
+    assert_eq!(book, PolyglotBook::from_bytes(&bytes).unwrap());
+    assert!(matches!(
+        PolyglotBook::from_bytes(&bytes[1..]),
+        Err(BookError::InvalidLength(95))
+    ));
+
+    let moves = book.moves(&start);
+    assert_eq!(3, moves.len());
+    assert_eq!("d2d4", book.best_move(&start).unwrap().to_string().as_str());
+    let picks: Vec<_> = [0, 9, 10, 39, 40, 49, 50]
+        .into_iter()
+        .map(|r| book.weighted_move(&start, r).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        vec!["e2e4", "e2e4", "d2d4", "d2d4", "g1f3", "g1f3", "e2e4"],
+        picks
+    );
+
+    // castling is encoded as the king taking its rook
+    let moves = book.moves(&castle);
+    assert_eq!(Some(Side::Queen), moves[0].0.castling());
+    assert_eq!(Square::C8, moves[0].0.destination_square());
+    assert_eq!(Some(Side::King), moves[1].0.castling());
+    assert!(book
+        .moves(&Board::try_from("8/8/8/8/8/8/8/K6k w - - 0 1").unwrap())
+        .is_empty());
+}
+
+const GAMES: &str = "
+[Result \"1-0\"]
+1. e4 e5 2. Nf3 Nc6 1-0
+
+[Result \"1/2-1/2\"]
+1. e4 c5 2. Nf3 1/2-1/2
+
+[Result \"0-1\"]
+1. d4 d5 2. c4 0-1
+
+[Result \"*\"]
+1. c4 *
+
+1. e4 e5 2. Ke3 1-0
+
+[Result \"1-0\"]
+1. e4 e5 2. Nf3 Nf6 1-0
+";
+
+fn weights(book: &PolyglotBook, board: &Board) -> Vec<(String, u16)> {
+    book.moves(board)
+        .into_iter()
+        .map(|(chess_move, weight)| (chess_move.to_string(), weight))
+        .collect()
+}
+
+#[test]
+fn test_polyglot_builder() {
+    init();
+    let start = Board::default();
+    let after_e4 = start.parse_uci_move("e2e4").unwrap().board;
+    let after_e5 = after_e4.parse_uci_move("e7e5").unwrap().board;
+
+    let mut builder = BookBuilder::new(BookOptions::default());
+    let errors = builder.add_games(read_games(GAMES));
+    assert_eq!(1, errors.len());
+    let book = builder.build();
+
+    // e4 won twice and drew once, d4 lost and the unfinished game doesn't count. Black's e5 only
+    // lost, so it is left out.
+    assert_eq!(vec![("e2e4".to_string(), 5)], weights(&book, &start));
+    assert_eq!(vec![("c7c5".to_string(), 1)], weights(&book, &after_e4));
+    assert_eq!(vec![("g1f3".to_string(), 4)], weights(&book, &after_e5));
+
+    // the book is sorted and can be read back
+    let bytes = book.to_bytes();
+    assert!(book.entries().windows(2).all(|w| w[0].key <= w[1].key));
+    assert_eq!(book, PolyglotBook::from_bytes(&bytes).unwrap());
+
+    // black's moves only, from positions reached in at least two games, one ply deep
+    let mut builder = BookBuilder::new(BookOptions {
+        white: false,
+        min_games: 2,
+        max_ply: 2,
+        draw_score: 3,
+        loss_score: 1,
+        ..Default::default()
+    });
+    builder.add_games(read_games(GAMES));
+    let book = builder.build();
+    assert!(book.moves(&start).is_empty());
+    assert_eq!(vec![("e7e5".to_string(), 2)], weights(&book, &after_e4));
+    assert_eq!(1, book.entries().len());
+}
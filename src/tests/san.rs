@@ -0,0 +1,98 @@
+use super::init;
+use crate::board::Board;
+use crate::utils::fen::*;
+use crate::utils::uci::MoveParseError;
+
+fn san(fen: &str, uci: &str) -> String {
+    let board = Board::try_from(fen).unwrap();
+    board.san(&board.parse_uci_move(uci).unwrap())
+}
+
+#[test]
+fn test_san_format() {
+    init();
+    assert_eq!("e4", san(START_POSITION, "e2e4"));
+    assert_eq!("Nf3", san(START_POSITION, "g1f3"));
+    assert_eq!(
+        "Nbd7",
+        san(
+            "rn1qkb1r/ppp1pppp/5n2/3p4/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1",
+            "b8d7"
+        )
+    );
+    assert_eq!("R1a3", san("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1", "a1a3"));
+    assert_eq!("Qa1b2", san("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "a1b2"));
+    assert_eq!("exd6", san("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"));
+    assert_eq!("O-O", san(TRICKY_POSITION, "e1g1"));
+    assert_eq!("O-O-O", san(TRICKY_POSITION, "e1c1"));
+    assert_eq!("Bxa6", san(TRICKY_POSITION, "e2a6"));
+    assert_eq!("e8=Q+", san("7k/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"));
+    assert_eq!("gxh8=N", san(KILLER_POSITION, "g7h8n"));
+    assert_eq!(
+        "Qh4#",
+        san(
+            "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq g3 0 2",
+            "d8h4"
+        )
+    );
+}
+
+#[test]
+fn test_san_round_trip() {
+    init();
+    for fen in [
+        START_POSITION,
+        TRICKY_POSITION,
+        KILLER_POSITION,
+        CMK_POSITION,
+        PROMOTION_POSITION,
+        "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1",
+    ] {
+        let board = Board::try_from(fen).unwrap();
+        for m in board.generate_legal_moves() {
+            assert_eq!(m, board.parse_san(&board.san(&m)).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_san_lenient() {
+    init();
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    assert_eq!(board.parse_uci_move("e1g1"), board.parse_san("0-0"));
+    assert_eq!(board.parse_uci_move("e1c1"), board.parse_san("O-O-O!?"));
+    assert_eq!(board.parse_uci_move("e2a6"), board.parse_san("Be2xa6"));
+
+    let board = Board::try_from("7k/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let promotion = board.parse_uci_move("e7e8q");
+    assert_eq!(promotion, board.parse_san("e8=Q+"));
+    assert_eq!(promotion, board.parse_san("e8=Q"));
+    assert_eq!(promotion, board.parse_san("e8Q"));
+    assert_eq!(promotion, board.parse_san("e8q!"));
+
+    let board = Board::try_from("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert_eq!(board.parse_uci_move("e5d6"), board.parse_san("exd6 e.p."));
+    assert_eq!(board.parse_uci_move("e5d6"), board.parse_san("ed6"));
+}
+
+#[test]
+fn test_san_errors() {
+    init();
+    let board = Board::try_from("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+    for s in ["", "Zf3", "e9", "Nf", "R1a1a3", "!!"] {
+        assert_eq!(
+            Err(MoveParseError::Malformed(s.to_string())),
+            board.parse_san(s)
+        );
+    }
+    for s in ["Nf3", "Rb3", "O-O", "e4"] {
+        assert_eq!(
+            Err(MoveParseError::Illegal(s.to_string())),
+            board.parse_san(s)
+        );
+    }
+    assert_eq!(
+        Err(MoveParseError::Ambiguous("Ra3".to_string())),
+        board.parse_san("Ra3")
+    );
+}
@@ -0,0 +1,60 @@
+use super::init;
+use crate::{bishopTargets, queenTargets, rookTargets};
+
+/// Walks the rays from `square`, stopping at the first blocker of each.
+fn ray_attacks(square: i32, occupancy: u64, directions: [(i32, i32); 4]) -> u64 {
+    let mut res = 0;
+    for (file_step, rank_step) in directions {
+        let (mut file, mut rank) = (square % 8 + file_step, square / 8 + rank_step);
+        while (0..8).contains(&file) && (0..8).contains(&rank) {
+            res |= 1 << (rank * 8 + file);
+            if occupancy & 1 << (rank * 8 + file) != 0 {
+                break;
+            }
+            file += file_step;
+            rank += rank_step;
+        }
+    }
+    res
+}
+
+const ROOK: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+#[test]
+fn test_slider_targets() {
+    init();
+    let mut state = 0x2545F4914F6CDD1Du64;
+    for square in 0..64 {
+        for _ in 0..2000 {
+            // xorshift, with sparser and denser boards mixed in
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let occupancy = match state % 3 {
+                0 => state & state.rotate_left(21),
+                1 => state,
+                _ => state | state.rotate_left(21),
+            };
+            let rook = ray_attacks(square, occupancy, ROOK);
+            let bishop = ray_attacks(square, occupancy, BISHOP);
+            unsafe {
+                assert_eq!(
+                    rook,
+                    rookTargets(square, occupancy),
+                    "{square} {occupancy:#x}"
+                );
+                assert_eq!(
+                    bishop,
+                    bishopTargets(square, occupancy),
+                    "{square} {occupancy:#x}"
+                );
+                assert_eq!(rook | bishop, queenTargets(square, occupancy));
+            }
+        }
+        unsafe {
+            assert_eq!(ray_attacks(square, 0, ROOK), rookTargets(square, 0));
+            assert_eq!(ray_attacks(square, !0, BISHOP), bishopTargets(square, !0));
+        }
+    }
+}
@@ -26,7 +26,7 @@ fn test_extraction() {
 
 #[test]
 fn test_fen() {
-    let board = Board::from(START_POSITION);
+    let board = Board::try_from(START_POSITION).unwrap();
     assert_eq!(vec![a1, h1], extract_squares(board.base.white.rooks));
     assert_eq!(vec![b1, g1], extract_squares(board.base.white.knights));
     assert_eq!(vec![c1, f1], extract_squares(board.base.white.bishops));
@@ -0,0 +1,58 @@
+use super::init;
+use crate::board::Board;
+use crate::utils::fen::*;
+use crate::utils::uci::MoveParseError;
+
+#[test]
+fn test_uci_round_trip() {
+    init();
+    for fen in [
+        START_POSITION,
+        TRICKY_POSITION,
+        KILLER_POSITION,
+        CMK_POSITION,
+        PROMOTION_POSITION,
+    ] {
+        let board = Board::try_from(fen).unwrap();
+        for m in board.generate_legal_moves() {
+            assert_eq!(m, board.parse_uci_move(&m.to_string()).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_uci_format() {
+    init();
+    let board = Board::try_from(START_POSITION).unwrap();
+    assert_eq!("e2e4", board.parse_uci_move("e2e4").unwrap().to_string());
+
+    let board = Board::try_from(TRICKY_POSITION).unwrap();
+    assert_eq!("e1g1", board.parse_uci_move("e1g1").unwrap().to_string());
+    assert_eq!("e1c1", board.parse_uci_move("e1c1").unwrap().to_string());
+
+    let board = Board::try_from(KILLER_POSITION).unwrap();
+    assert_eq!("g7h8q", board.parse_uci_move("g7h8q").unwrap().to_string());
+    assert_eq!("g7g8n", board.parse_uci_move("g7g8n").unwrap().to_string());
+    assert_eq!("f5e6", board.parse_uci_move("f5e6").unwrap().to_string());
+}
+
+#[test]
+fn test_uci_errors() {
+    init();
+    let board = Board::try_from(KILLER_POSITION).unwrap();
+    for s in [
+        "", "e2", "e2e4e", "e2e9", "i2i4", "g7g8k", "g7g8Q", "e2-e4", "é2e4",
+    ] {
+        assert_eq!(
+            Err(MoveParseError::Malformed(s.to_string())),
+            board.parse_uci_move(s)
+        );
+    }
+    // well-formed, but not legal here
+    for s in ["e2e5", "e7e5", "g7g8", "e1g1", "a1a2"] {
+        assert_eq!(
+            Err(MoveParseError::Illegal(s.to_string())),
+            board.parse_uci_move(s)
+        );
+    }
+}
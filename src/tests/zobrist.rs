@@ -0,0 +1,57 @@
+use super::init;
+use crate::board::Board;
+
+fn play(board: &Board, moves: &[&str]) -> Board {
+    moves.iter().fold(*board, |board, uci| {
+        board.parse_uci_move(uci).unwrap().board
+    })
+}
+
+#[test]
+fn test_zobrist_transpositions() {
+    init();
+    let start = Board::default();
+
+    // the clocks are not part of the key
+    let back = play(&start, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+    assert_eq!(start.zobrist_key(), back.zobrist_key());
+    assert_ne!(start, back);
+
+    let a = play(&start, &["e2e4", "e7e5", "g1f3", "b8c6"]);
+    let b = play(&start, &["g1f3", "e7e5", "e2e4", "b8c6"]);
+    assert_eq!(a.zobrist_key(), b.zobrist_key());
+
+    // incremental keys agree with boards parsed from scratch
+    let parsed =
+        Board::try_from("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+            .unwrap();
+    assert_eq!(parsed.zobrist_key(), a.zobrist_key());
+}
+
+#[test]
+fn test_zobrist_state() {
+    init();
+    let start = Board::default();
+
+    // side to move
+    let white = Board::try_from("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let black = Board::try_from("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(white.zobrist_key(), black.zobrist_key());
+
+    // castling rights are lost when the king walks back
+    let walked = play(&start, &["e2e4", "e7e5", "e1e2", "e8e7", "e2e1", "e7e8"]);
+    let pushed = play(&start, &["e2e4", "e7e5"]);
+    assert_ne!(walked.zobrist_key(), pushed.zobrist_key());
+    assert_eq!(
+        Board::try_from("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 4")
+            .unwrap()
+            .zobrist_key(),
+        walked.zobrist_key()
+    );
+
+    // en passant square
+    let double = play(&start, &["e2e4"]);
+    let without =
+        Board::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_ne!(double.zobrist_key(), without.zobrist_key());
+}
@@ -0,0 +1,462 @@
+use super::{
+    fen::{parse_clock, parse_fullmove, FenError},
+    uci::MoveParseError,
+};
+use crate::board::{Board, ChessMove};
+use std::{error::Error, fmt::Display, str::FromStr};
+
+/// An operation of an EPD record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdOp {
+    /// `bm`: the moves considered best in the position.
+    BestMoves(Vec<ChessMove>),
+    /// `am`: the moves to avoid in the position.
+    AvoidMoves(Vec<ChessMove>),
+    /// `id`: the name of the position, usually unique within a test suite.
+    Id(String),
+    /// `c0` to `c9`: comments, numbered by the digit of their opcode.
+    Comment(u8, String),
+    /// `acd`: the depth of the analysis behind the record, in plies.
+    AnalysisDepth(u32),
+    /// `ce`: the evaluation of the position in centipawns, from the point of view of the side
+    /// to move.
+    CentipawnEvaluation(i32),
+    /// `D1`, `D2`, ...: the number of leaf nodes of a perft search of the given depth.
+    Perft { depth: u32, nodes: u64 },
+    /// `hmvc`: the halfmove clock of the position.
+    HalfmoveClock(i32),
+    /// `fmvn`: the fullmove number of the position.
+    FullmoveNumber(i32),
+    /// Any other operation, with its operands as written.
+    Other(String, Vec<String>),
+}
+
+/// Reasons an EPD record can be rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError {
+    /// The first four fields are not a valid FEN position.
+    InvalidFen(FenError),
+    /// An opcode that does not start with a letter or contains other symbols than letters,
+    /// digits and underscores.
+    InvalidOpcode(String),
+    /// An operand that does not fit its opcode, or a missing operand.
+    InvalidOperand { opcode: String, operand: String },
+    /// A move operand that is not legal in the position.
+    InvalidMove(MoveParseError),
+    /// A string operand without a closing quote.
+    UnterminatedString,
+}
+impl Display for EpdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFen(e) => write!(f, "{e}"),
+            Self::InvalidOpcode(opcode) => write!(f, "invalid opcode '{opcode}'"),
+            Self::InvalidOperand { opcode, operand } if operand.is_empty() => {
+                write!(f, "missing operand for '{opcode}'")
+            }
+            Self::InvalidOperand { opcode, operand } => {
+                write!(f, "invalid operand '{operand}' for '{opcode}'")
+            }
+            Self::InvalidMove(e) => write!(f, "{e}"),
+            Self::UnterminatedString => write!(f, "unterminated string operand"),
+        }
+    }
+}
+impl Error for EpdError {}
+
+/// A position in Extended Position Description: the first four fields of a FEN record followed
+/// by operations terminated by semicolons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epd {
+    /// The position. EPD has no clock fields, so the clocks are "0 1" unless the `hmvc` and
+    /// `fmvn` operations set them.
+    pub board: Board,
+    pub ops: Vec<EpdOp>,
+}
+impl Epd {
+    /// Returns the operand of the `id` operation, if there is one.
+    pub fn id(&self) -> Option<&str> {
+        self.ops.iter().find_map(|op| match op {
+            EpdOp::Id(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for Epd {
+    type Err = EpdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // The position ends with the fourth field. Parsing it in place keeps the columns of FEN
+        // errors pointing into the whole record.
+        let mut end = 0;
+        for _ in 0..4 {
+            let start = value[end..]
+                .find(|c: char| !c.is_whitespace())
+                .map_or(value.len(), |i| end + i);
+            end = value[start..]
+                .find(char::is_whitespace)
+                .map_or(value.len(), |i| start + i);
+        }
+        let mut board = Board::from_str(&value[..end]).map_err(EpdError::InvalidFen)?;
+        let ops = parse_operations(&board, &value[end..])?;
+        for op in &ops {
+            match *op {
+                EpdOp::HalfmoveClock(halfmove) => board.halfmove = halfmove,
+                EpdOp::FullmoveNumber(fullmove) => board.fullmove = fullmove,
+                _ => (),
+            }
+        }
+        Ok(Self { board, ops })
+    }
+}
+
+impl TryFrom<&str> for Epd {
+    type Error = EpdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Writes the record with moves in SAN and every operation terminated by a semicolon.
+impl Display for Epd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fen = self.board.to_fen();
+        let fields: Vec<_> = fen.split(' ').take(4).collect();
+        write!(f, "{}", fields.join(" "))?;
+
+        let write_moves = |f: &mut std::fmt::Formatter<'_>, moves: &[ChessMove]| {
+            for chess_move in moves {
+                write!(f, " {}", self.board.san(chess_move))?;
+            }
+            Ok(())
+        };
+        for op in &self.ops {
+            match op {
+                EpdOp::BestMoves(moves) => {
+                    write!(f, " bm")?;
+                    write_moves(f, moves)?;
+                }
+                EpdOp::AvoidMoves(moves) => {
+                    write!(f, " am")?;
+                    write_moves(f, moves)?;
+                }
+                EpdOp::Id(id) => write!(f, " id \"{id}\"")?,
+                EpdOp::Comment(n, comment) => write!(f, " c{n} \"{comment}\"")?,
+                EpdOp::AnalysisDepth(depth) => write!(f, " acd {depth}")?,
+                EpdOp::CentipawnEvaluation(eval) => write!(f, " ce {eval}")?,
+                EpdOp::Perft { depth, nodes } => write!(f, " D{depth} {nodes}")?,
+                EpdOp::HalfmoveClock(halfmove) => write!(f, " hmvc {halfmove}")?,
+                EpdOp::FullmoveNumber(fullmove) => write!(f, " fmvn {fullmove}")?,
+                EpdOp::Other(opcode, operands) => {
+                    write!(f, " {opcode}")?;
+                    for operand in operands {
+                        if operand.contains(char::is_whitespace) || operand.is_empty() {
+                            write!(f, " \"{operand}\"")?;
+                        } else {
+                            write!(f, " {operand}")?;
+                        }
+                    }
+                }
+            }
+            write!(f, ";")?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the operations following the position into opcodes and operands.
+fn parse_operations(board: &Board, s: &str) -> Result<Vec<EpdOp>, EpdError> {
+    let mut ops = Vec::new();
+    let mut operation = Vec::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        let len = match c {
+            ';' => {
+                ops.push(parse_operation(board, std::mem::take(&mut operation))?);
+                1
+            }
+            '"' => {
+                let end = rest[1..].find('"').ok_or(EpdError::UnterminatedString)? + 1;
+                operation.push(&rest[1..end]);
+                end + 1
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == ';')
+                    .unwrap_or(rest.len());
+                operation.push(&rest[..end]);
+                end
+            }
+        };
+        rest = &rest[len..];
+    }
+    // the last semicolon is often left out
+    if !operation.is_empty() {
+        ops.push(parse_operation(board, operation)?);
+    }
+    Ok(ops)
+}
+
+fn parse_operation(board: &Board, operation: Vec<&str>) -> Result<EpdOp, EpdError> {
+    let opcode = operation.first().copied().unwrap_or_default();
+    if !opcode.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !opcode
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(EpdError::InvalidOpcode(opcode.to_string()));
+    }
+    let operands = &operation[1..];
+    let invalid = |operand: &str| EpdError::InvalidOperand {
+        opcode: opcode.to_string(),
+        operand: operand.to_string(),
+    };
+    // the single operand of an operation that takes exactly one
+    let single = || match operands {
+        [operand] => Ok(*operand),
+        [] => Err(invalid("")),
+        [_, extra, ..] => Err(invalid(extra)),
+    };
+    // clocks are written the same way as in a FEN record
+    let clock = |parse: fn(&str) -> Option<i32>| {
+        let operand = single()?;
+        parse(operand).ok_or_else(|| invalid(operand))
+    };
+    let moves = || {
+        if operands.is_empty() {
+            return Err(invalid(""));
+        }
+        operands
+            .iter()
+            .map(|san| board.parse_san(san).map_err(EpdError::InvalidMove))
+            .collect()
+    };
+
+    let op = match opcode {
+        "bm" => EpdOp::BestMoves(moves()?),
+        "am" => EpdOp::AvoidMoves(moves()?),
+        "id" => EpdOp::Id(single()?.to_string()),
+        "acd" => {
+            let operand = single()?;
+            EpdOp::AnalysisDepth(operand.parse().map_err(|_| invalid(operand))?)
+        }
+        "ce" => {
+            let operand = single()?;
+            EpdOp::CentipawnEvaluation(operand.parse().map_err(|_| invalid(operand))?)
+        }
+        "hmvc" => EpdOp::HalfmoveClock(clock(parse_clock)?),
+        "fmvn" => EpdOp::FullmoveNumber(clock(parse_fullmove)?),
+        _ => {
+            if let Some(n) = comment_number(opcode) {
+                EpdOp::Comment(n, single()?.to_string())
+            } else if let Some(depth) = perft_depth(opcode) {
+                let operand = single()?;
+                let nodes = operand.parse().map_err(|_| invalid(operand))?;
+                EpdOp::Perft { depth, nodes }
+            } else {
+                EpdOp::Other(
+                    opcode.to_string(),
+                    operands.iter().map(|s| s.to_string()).collect(),
+                )
+            }
+        }
+    };
+    Ok(op)
+}
+
+/// The digit of a comment opcode `c0` to `c9`.
+fn comment_number(opcode: &str) -> Option<u8> {
+    match opcode.as_bytes() {
+        [b'c', n @ b'0'..=b'9'] => Some(n - b'0'),
+        _ => None,
+    }
+}
+
+/// The depth of a perft opcode such as `D5`.
+fn perft_depth(opcode: &str) -> Option<u32> {
+    let depth = opcode.strip_prefix('D')?;
+    if depth.chars().all(|c| c.is_ascii_digit()) {
+        depth.parse().ok().filter(|&depth| depth > 0)
+    } else {
+        None
+    }
+}
+
+// --- Test suites ---
+
+/// The outcome of checking one operation of a test position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdCheck {
+    Perft {
+        depth: u32,
+        expected: u64,
+        found: u64,
+    },
+    /// A `bm` operation, passed when the chosen move is one of the best moves.
+    BestMove {
+        expected: Vec<ChessMove>,
+        found: ChessMove,
+    },
+    /// An `am` operation, passed when the chosen move is none of the moves to avoid.
+    AvoidMove {
+        avoid: Vec<ChessMove>,
+        found: ChessMove,
+    },
+}
+impl EpdCheck {
+    pub fn passed(&self) -> bool {
+        match self {
+            Self::Perft {
+                expected, found, ..
+            } => expected == found,
+            Self::BestMove { expected, found } => expected.contains(found),
+            Self::AvoidMove { avoid, found } => !avoid.contains(found),
+        }
+    }
+}
+
+/// The checks of one line of a test suite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdCase {
+    /// The line number within the suite, starting at 1.
+    pub line: usize,
+    pub id: Option<String>,
+    /// The checks that were run, or why the line could not be parsed.
+    pub checks: Result<Vec<EpdCheck>, EpdError>,
+}
+impl EpdCase {
+    pub fn passed(&self) -> bool {
+        self.checks
+            .as_ref()
+            .is_ok_and(|checks| checks.iter().all(EpdCheck::passed))
+    }
+}
+
+/// The results of running a test suite, with one case for every position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdReport {
+    pub cases: Vec<EpdCase>,
+}
+impl EpdReport {
+    pub fn passed(&self) -> usize {
+        self.cases.iter().filter(|case| case.passed()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.cases.len() - self.passed()
+    }
+}
+impl Display for EpdReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for case in &self.cases {
+            let status = if case.passed() { "PASS" } else { "FAIL" };
+            write!(f, "{status} line {}", case.line)?;
+            if let Some(id) = &case.id {
+                write!(f, " ({id})")?;
+            }
+            match &case.checks {
+                Ok(checks) => {
+                    for check in checks.iter().filter(|check| !check.passed()) {
+                        match check {
+                            EpdCheck::Perft {
+                                depth,
+                                expected,
+                                found,
+                            } => write!(f, "; D{depth} expected {expected}, found {found}")?,
+                            EpdCheck::BestMove { found, .. } => {
+                                write!(f, "; {found} is not a best move")?
+                            }
+                            EpdCheck::AvoidMove { found, .. } => {
+                                write!(f, "; {found} should be avoided")?
+                            }
+                        }
+                    }
+                }
+                Err(e) => write!(f, "; {e}")?,
+            }
+            writeln!(f)?;
+        }
+        write!(f, "{}/{} passed", self.passed(), self.cases.len())
+    }
+}
+
+/// Runs a test suite of EPD records, one per line. Blank lines and lines starting with '#' are
+/// skipped.
+///
+/// Perft operations are checked against move generation up to `max_perft_depth`, and deeper ones
+/// are left out. `bm` and `am` operations are checked against the move picked by `choose_move`,
+/// or left out without one.
+pub fn run_epd_suite(
+    suite: &str,
+    max_perft_depth: u32,
+    mut choose_move: Option<&mut dyn FnMut(&Board) -> ChessMove>,
+) -> EpdReport {
+    let mut cases = Vec::new();
+    for (i, line) in suite.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let epd = match Epd::from_str(line) {
+            Ok(epd) => epd,
+            Err(e) => {
+                cases.push(EpdCase {
+                    line: i + 1,
+                    id: None,
+                    checks: Err(e),
+                });
+                continue;
+            }
+        };
+
+        // the engine is only asked once, however many move operations there are
+        let mut chosen = None;
+        let mut choose = |board: &Board| -> Option<ChessMove> {
+            let choose_move = choose_move.as_mut()?;
+            Some(*chosen.get_or_insert_with(|| choose_move(board)))
+        };
+
+        let mut checks = Vec::new();
+        for op in &epd.ops {
+            match op {
+                EpdOp::Perft { depth, nodes } if *depth <= max_perft_depth => {
+                    checks.push(EpdCheck::Perft {
+                        depth: *depth,
+                        expected: *nodes,
+                        found: epd.board.perft(*depth).nodes,
+                    })
+                }
+                EpdOp::BestMoves(moves) => {
+                    if let Some(found) = choose(&epd.board) {
+                        checks.push(EpdCheck::BestMove {
+                            expected: moves.clone(),
+                            found,
+                        });
+                    }
+                }
+                EpdOp::AvoidMoves(moves) => {
+                    if let Some(found) = choose(&epd.board) {
+                        checks.push(EpdCheck::AvoidMove {
+                            avoid: moves.clone(),
+                            found,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        cases.push(EpdCase {
+            line: i + 1,
+            id: epd.id().map(str::to_string),
+            checks: Ok(checks),
+        });
+    }
+    EpdReport { cases }
+}
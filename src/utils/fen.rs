@@ -1,9 +1,9 @@
-use super::{Colour, Pieces};
-use crate::{board::Board, castling_rights, createBase, piece_map_bitboards, utils::squares};
-use core::panic;
-use std::convert::From;
+use super::{Colour, Piece, Pieces};
+use crate::{
+    board::Board, castling_rights, createBase, piece_map_bitboards, Bitboard, Rank, Square,
+};
+use std::{convert::From, error::Error, fmt::Display, str::FromStr};
 
-pub const EMPTY_BOARD: &str = "8/8/8/8/8/8/8/8 w - - ";
 pub const START_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ";
 pub const CASTLE_KINGSIDE_POSITION: &str =
     "rnbqk2r/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w KQkq - 0 1 ";
@@ -20,67 +20,460 @@ pub const KILLER_POSITION: &str =
 pub const CMK_POSITION: &str =
     "r2q1rk1/ppp2ppp/2n1bn2/2b1p3/3pP3/3P1NPP/PPP1NPB1/R1BQ1RK1 b - - 0 9 ";
 
-/// Create a chess board instance from fen
-impl From<&str> for Board {
-    fn from(value: &str) -> Self {
-        let s = value.split_at(value.find(" ").unwrap());
-        let ranks = s.0.split("/");
-        let mut args = s.1.split(" ");
-
-        let mut white = Pieces::default();
-        let mut black = Pieces::default();
-        let mut i: i32 = 63;
-        for rank in ranks {
-            for c in rank.chars().rev() {
-                if let Ok(n) = c.to_string().parse::<i32>() {
-                    i -= n;
-                } else {
-                    match c {
-                        'p' => black.pawns.push(i),
-                        'P' => white.pawns.push(i),
-                        'k' => black.king.push(i),
-                        'K' => white.king.push(i),
-                        'q' => black.queens.push(i),
-                        'Q' => white.queens.push(i),
-                        'b' => black.bishops.push(i),
-                        'B' => white.bishops.push(i),
-                        'n' => black.knights.push(i),
-                        'N' => white.knights.push(i),
-                        'r' => black.rooks.push(i),
-                        'R' => white.rooks.push(i),
-                        _ => panic!("Invalid symbol '{c}'"),
-                    };
-                    i -= 1;
-                }
+/// The fields of a FEN record, used to point out where parsing failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+    PiecePlacement,
+    SideToMove,
+    CastlingRights,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+impl Display for FenField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PiecePlacement => write!(f, "piece placement"),
+            Self::SideToMove => write!(f, "side to move"),
+            Self::CastlingRights => write!(f, "castling rights"),
+            Self::EnPassant => write!(f, "en passant square"),
+            Self::HalfmoveClock => write!(f, "halfmove clock"),
+            Self::FullmoveNumber => write!(f, "fullmove number"),
+        }
+    }
+}
+
+/// Reasons a FEN string can be rejected.
+///
+/// Ranks are numbered 8-1 as they appear in the FEN, and columns are character offsets into
+/// the whole FEN string so that the offending symbol can be pointed out to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    /// A required field is missing.
+    MissingField(FenField),
+    /// There are fields after the fullmove number.
+    TrailingField {
+        column: usize,
+        value: String,
+    },
+    /// The piece placement does not consist of exactly eight ranks.
+    InvalidRankCount(usize),
+    /// A rank does not describe exactly eight squares.
+    InvalidRankLength {
+        rank: usize,
+        column: usize,
+    },
+    /// A symbol in the piece placement is neither a piece nor a digit 1-8.
+    InvalidPiece {
+        rank: usize,
+        column: usize,
+        symbol: char,
+    },
+    InvalidSideToMove {
+        column: usize,
+        value: String,
+    },
+    InvalidCastlingRights {
+        column: usize,
+        value: String,
+    },
+    InvalidEnPassant {
+        column: usize,
+        value: String,
+    },
+    InvalidHalfmoveClock {
+        column: usize,
+        value: String,
+    },
+    InvalidFullmoveNumber {
+        column: usize,
+        value: String,
+    },
+    /// A side does not have exactly one king.
+    InvalidKingCount {
+        colour: Colour,
+        count: u32,
+    },
+    /// A pawn stands on the first or eighth rank.
+    PawnOnBackRank(Square),
+    /// A castling right is held without the king and that rook on their starting squares.
+    UnavailableCastlingRight {
+        column: usize,
+        right: char,
+    },
+    /// The side that is not to move is in check, so its king could be captured.
+    OpponentInCheck,
+}
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing {field} field"),
+            Self::TrailingField { column, value } => {
+                write!(f, "unexpected field '{value}' at column {column}")
+            }
+            Self::InvalidRankCount(n) => write!(f, "expected 8 ranks, found {n}"),
+            Self::InvalidRankLength { rank, column } => write!(
+                f,
+                "rank {rank} does not describe 8 squares (column {column})"
+            ),
+            Self::InvalidPiece {
+                rank,
+                column,
+                symbol,
+            } => write!(
+                f,
+                "invalid symbol '{symbol}' on rank {rank} (column {column})"
+            ),
+            Self::InvalidSideToMove { column, value } => {
+                write!(f, "invalid side to move '{value}' at column {column}")
             }
+            Self::InvalidCastlingRights { column, value } => {
+                write!(f, "invalid castling rights '{value}' at column {column}")
+            }
+            Self::InvalidEnPassant { column, value } => {
+                write!(f, "invalid en passant square '{value}' at column {column}")
+            }
+            Self::InvalidHalfmoveClock { column, value } => {
+                write!(f, "invalid halfmove clock '{value}' at column {column}")
+            }
+            Self::InvalidFullmoveNumber { column, value } => {
+                write!(f, "invalid fullmove number '{value}' at column {column}")
+            }
+            Self::InvalidKingCount { colour, count } => {
+                let colour = match colour {
+                    Colour::White => "white",
+                    Colour::Black => "black",
+                };
+                write!(f, "expected one {colour} king, found {count}")
+            }
+            Self::PawnOnBackRank(square) => write!(f, "pawn on the back rank at {square}"),
+            Self::UnavailableCastlingRight { column, right } => write!(
+                f,
+                "castling right '{right}' at column {column} needs the king and rook on their \
+                 starting squares"
+            ),
+            Self::OpponentInCheck => write!(f, "the side not to move is in check"),
         }
+    }
+}
+impl Error for FenError {}
+
+/// Create a chess board instance from fen
+impl FromStr for Board {
+    type Err = FenError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // Pair every field with its column so errors can point at the offending text
+        let mut fields = value
+            .split(' ')
+            .scan(0, |column, field| {
+                let start = *column;
+                *column += field.len() + 1;
+                Some((start, field))
+            })
+            .filter(|(_, field)| !field.is_empty());
+
+        let (placement_column, placement) = fields
+            .next()
+            .ok_or(FenError::MissingField(FenField::PiecePlacement))?;
+        let (mut white, mut black) = parse_placement(placement, placement_column)?;
+
+        let side_to_move = match fields.next() {
+            Some((_, "w")) => Colour::White,
+            Some((_, "b")) => Colour::Black,
+            Some((column, value)) => {
+                return Err(FenError::InvalidSideToMove {
+                    column,
+                    value: value.to_string(),
+                })
+            }
+            None => return Err(FenError::MissingField(FenField::SideToMove)),
+        };
 
-        args.next();
-        let side_to_move = match args.next().unwrap() {
-            "w" => Colour::White,
-            "b" => Colour::Black,
-            _ => panic!("Invalid side to move"),
+        let castling = fields
+            .next()
+            .ok_or(FenError::MissingField(FenField::CastlingRights))?;
+        let castling_rights = parse_castling_rights(castling.1, castling.0)?;
+
+        let en_passant = match fields.next() {
+            Some((_, "-")) => None,
+            Some((column, value)) => {
+                let error = FenError::InvalidEnPassant {
+                    column,
+                    value: value.to_string(),
+                };
+                Some((value.parse::<Square>().map_err(|_| error.clone())?, error))
+            }
+            None => return Err(FenError::MissingField(FenField::EnPassant)),
         };
 
-        let castling_rights = castling_rights::from(args.next().unwrap());
-        let killer_square = squares::from_str(args.next().unwrap());
-        let halfmove = args.next().unwrap().parse::<i32>().unwrap();
-        let fullmove = args.next().unwrap().parse::<i32>().unwrap();
+        // The clocks are optional and default to "0 1"
+        let halfmove = match fields.next() {
+            Some((column, value)) => {
+                parse_clock(value).ok_or_else(|| FenError::InvalidHalfmoveClock {
+                    column,
+                    value: value.to_string(),
+                })?
+            }
+            None => 0,
+        };
+        let fullmove = match fields.next() {
+            Some((column, value)) => {
+                parse_fullmove(value).ok_or_else(|| FenError::InvalidFullmoveNumber {
+                    column,
+                    value: value.to_string(),
+                })?
+            }
+            None => 1,
+        };
+
+        if let Some((column, value)) = fields.next() {
+            return Err(FenError::TrailingField {
+                column,
+                value: value.to_string(),
+            });
+        }
 
-        unsafe {
-            let base = createBase(
+        let base = unsafe {
+            createBase(
                 piece_map_bitboards::from(&mut white),
                 piece_map_bitboards::from(&mut black),
-            );
-            Self::new(
-                base,
-                killer_square,
-                castling_rights,
-                side_to_move,
-                halfmove,
-                fullmove,
             )
+        };
+        let board = Self::new(
+            base,
+            en_passant.as_ref().map(|(square, _)| *square),
+            castling_rights,
+            side_to_move,
+            halfmove,
+            fullmove,
+        );
+
+        // Only squares behind a pawn of the opponent that has just made a double push are valid
+        if let Some((square, error)) = en_passant {
+            let (rank, pawn) = match side_to_move {
+                Colour::White => (Rank::Sixth, square as i32 - 8),
+                Colour::Black => (Rank::Third, square as i32 + 8),
+            };
+            if square.rank() != rank
+                || board.piece_at(square as i32).is_some()
+                || board.piece_at(pawn) != Some(Piece::Pawn(side_to_move.other()))
+            {
+                return Err(error);
+            }
         }
+        check_position(&board, castling.1, castling.0)?;
+        Ok(board)
+    }
+}
+
+/// Rejects positions that are well-formed but could not come up in a game, as playing on from
+/// them would corrupt the board.
+fn check_position(board: &Board, castling: &str, column: usize) -> Result<(), FenError> {
+    for colour in [Colour::White, Colour::Black] {
+        let count = board.bitboard(Piece::King(colour)).count();
+        if count != 1 {
+            return Err(FenError::InvalidKingCount { colour, count });
+        }
+    }
+
+    let pawns =
+        board.bitboard(Piece::Pawn(Colour::White)) | board.bitboard(Piece::Pawn(Colour::Black));
+    if let Some(square) = (pawns & (Bitboard::RANK_1 | Bitboard::RANK_8)).first() {
+        return Err(FenError::PawnOnBackRank(square));
+    }
+
+    for (i, right) in castling.char_indices().filter(|&(_, c)| c != '-') {
+        let (colour, king, rook) = match right {
+            'K' => (Colour::White, Square::E1, Square::H1),
+            'Q' => (Colour::White, Square::E1, Square::A1),
+            'k' => (Colour::Black, Square::E8, Square::H8),
+            _ => (Colour::Black, Square::E8, Square::A8),
+        };
+        if board.piece_on(king) != Some(Piece::King(colour))
+            || board.piece_on(rook) != Some(Piece::Rook(colour))
+        {
+            return Err(FenError::UnavailableCastlingRight {
+                column: column + i,
+                right,
+            });
+        }
+    }
+
+    let opponent = board.side_to_move.other();
+    let king = board.bitboard(Piece::King(opponent)).0.trailing_zeros() as i32;
+    if board.is_attacked(king, board.side_to_move) {
+        return Err(FenError::OpponentInCheck);
+    }
+    Ok(())
+}
+
+impl TryFrom<&str> for Board {
+    type Error = FenError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+fn parse_placement(placement: &str, start: usize) -> Result<(Pieces, Pieces), FenError> {
+    let rank_count = placement.split('/').count();
+    if rank_count != 8 {
+        return Err(FenError::InvalidRankCount(rank_count));
+    }
+
+    let mut white = Pieces::default();
+    let mut black = Pieces::default();
+    let mut column = start;
+    for (i, rank) in placement.split('/').enumerate() {
+        let rank_number = 8 - i;
+        let mut file = 0;
+        for c in rank.chars() {
+            if file >= 8 {
+                return Err(FenError::InvalidRankLength {
+                    rank: rank_number,
+                    column,
+                });
+            }
+            let square = (rank_number as i32 - 1) * 8 + file as i32;
+            match c {
+                '1'..='8' => file += c as usize - '0' as usize,
+                _ => {
+                    match c {
+                        'p' => black.pawns.push(square),
+                        'P' => white.pawns.push(square),
+                        'k' => black.king.push(square),
+                        'K' => white.king.push(square),
+                        'q' => black.queens.push(square),
+                        'Q' => white.queens.push(square),
+                        'b' => black.bishops.push(square),
+                        'B' => white.bishops.push(square),
+                        'n' => black.knights.push(square),
+                        'N' => white.knights.push(square),
+                        'r' => black.rooks.push(square),
+                        'R' => white.rooks.push(square),
+                        _ => {
+                            return Err(FenError::InvalidPiece {
+                                rank: rank_number,
+                                column,
+                                symbol: c,
+                            })
+                        }
+                    };
+                    file += 1;
+                }
+            }
+            column += c.len_utf8();
+        }
+        if file != 8 {
+            return Err(FenError::InvalidRankLength {
+                rank: rank_number,
+                column,
+            });
+        }
+        // skip the separating '/'
+        column += 1;
+    }
+
+    Ok((white, black))
+}
+
+fn parse_castling_rights(value: &str, column: usize) -> Result<castling_rights, FenError> {
+    let error = || FenError::InvalidCastlingRights {
+        column,
+        value: value.to_string(),
+    };
+    if value == "-" {
+        return Ok(castling_rights::from(value));
+    }
+    // Every right may appear at most once, and nothing else may appear
+    for (i, c) in value.chars().enumerate() {
+        if !"KQkq".contains(c) || value[i + 1..].contains(c) {
+            return Err(error());
+        }
+    }
+    Ok(castling_rights::from(value))
+}
+
+pub(super) fn parse_clock(value: &str) -> Option<i32> {
+    // `i32::from_str` accepts a leading '+', which FEN does not
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// The fullmove number starts at 1, so unlike the halfmove clock it can't be 0.
+pub(super) fn parse_fullmove(value: &str) -> Option<i32> {
+    parse_clock(value).filter(|&fullmove| fullmove >= 1)
+}
+
+/// Display wrapper writing a board in FEN.
+pub struct Fen<'a>(pub &'a Board);
+impl Display for Fen<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let board = self.0;
+
+        // --- Piece placement ---
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                if let Some(piece) = board.piece_at(rank * 8 + file) {
+                    if empty != 0 {
+                        write!(f, "{empty}")?;
+                        empty = 0;
+                    }
+                    write!(f, "{}", piece.as_char())?;
+                } else {
+                    empty += 1;
+                }
+            }
+            if empty != 0 {
+                write!(f, "{empty}")?;
+            }
+            if rank != 0 {
+                write!(f, "/")?;
+            }
+        }
+
+        // --- Side to move ---
+        match board.side_to_move {
+            Colour::White => write!(f, " w ")?,
+            Colour::Black => write!(f, " b ")?,
+        }
+
+        // --- Castling rights ---
+        let rights = board.castling_rights;
+        if !(rights.white_king || rights.white_queen || rights.black_king || rights.black_queen) {
+            write!(f, "-")?;
+        }
+        for (right, symbol) in [
+            (rights.white_king, 'K'),
+            (rights.white_queen, 'Q'),
+            (rights.black_king, 'k'),
+            (rights.black_queen, 'q'),
+        ] {
+            if right {
+                write!(f, "{symbol}")?;
+            }
+        }
+
+        // --- En passant square ---
+        match board.en_passant() {
+            Some(square) => write!(f, " {square}")?,
+            None => write!(f, " -")?,
+        }
+
+        // --- Clocks ---
+        write!(f, " {} {}", board.halfmove, board.fullmove)
+    }
+}
+
+impl Board {
+    /// Serializes the board to FEN. The result can be parsed back into an identical board.
+    pub fn to_fen(&self) -> String {
+        Fen(self).to_string()
     }
 }
 
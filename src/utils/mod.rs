@@ -1,10 +1,13 @@
+pub mod epd;
 pub mod fen;
+pub mod san;
 pub mod squares;
+pub mod uci;
 
 use crate::{piece_map_bitboards, squares_to_bitboard};
 
 /// Enum representation of the colour of pieces.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Colour {
     White,
     Black,
@@ -24,7 +27,7 @@ impl Colour {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Piece {
     Pawn(Colour),
     King(Colour),
@@ -34,6 +37,31 @@ pub enum Piece {
     Rook(Colour),
 }
 impl Piece {
+    pub fn colour(&self) -> Colour {
+        match *self {
+            Self::Pawn(c)
+            | Self::King(c)
+            | Self::Queen(c)
+            | Self::Bishop(c)
+            | Self::Knight(c)
+            | Self::Rook(c) => c,
+        }
+    }
+    /// The letter used for the piece in FEN, upper case for white and lower case for black.
+    pub fn as_char(&self) -> char {
+        let c = match self {
+            Self::Pawn(_) => 'p',
+            Self::King(_) => 'k',
+            Self::Queen(_) => 'q',
+            Self::Bishop(_) => 'b',
+            Self::Knight(_) => 'n',
+            Self::Rook(_) => 'r',
+        };
+        match self.colour() {
+            Colour::White => c.to_ascii_uppercase(),
+            Colour::Black => c,
+        }
+    }
     pub fn symbol(&self) -> Option<&str> {
         match self {
             Self::Pawn(Colour::Black) => Some("♙"),
@@ -52,6 +80,7 @@ impl Piece {
     }
 }
 
+#[derive(Default)]
 pub struct Pieces {
     pub king: Vec<i32>,
     pub queens: Vec<i32>,
@@ -60,18 +89,6 @@ pub struct Pieces {
     pub rooks: Vec<i32>,
     pub pawns: Vec<i32>,
 }
-impl Default for Pieces {
-    fn default() -> Self {
-        Self {
-            king: vec![],
-            queens: vec![],
-            bishops: vec![],
-            knights: vec![],
-            rooks: vec![],
-            pawns: vec![],
-        }
-    }
-}
 impl From<piece_map_bitboards> for Pieces {
     fn from(value: piece_map_bitboards) -> Self {
         let king = extract_squares(value.king);
@@ -0,0 +1,202 @@
+use super::{uci::MoveParseError, Colour, Piece};
+use crate::{
+    board::{Board, ChessMove, Side},
+    File, Rank, Square,
+};
+use std::mem::discriminant;
+
+impl Board {
+    /// Writes a legal move of this position in Standard Algebraic Notation, e.g. "Nbd7", "exd6",
+    /// "O-O-O", "e8=Q+" or "Qh4#".
+    pub fn san(&self, chess_move: &ChessMove) -> String {
+        let mut res = match chess_move.castling() {
+            Some(Side::King) => String::from("O-O"),
+            Some(Side::Queen) => String::from("O-O-O"),
+            None => self.san_without_suffix(chess_move),
+        };
+
+        if chess_move.is_check() {
+            if chess_move.board.generate_legal_moves().is_empty() {
+                res.push('#');
+            } else {
+                res.push('+');
+            }
+        }
+        res
+    }
+
+    fn san_without_suffix(&self, chess_move: &ChessMove) -> String {
+        let from = chess_move.source_square();
+        let to = chess_move.destination_square();
+        let from_str = from.to_string();
+        let mut res = String::new();
+
+        if let Piece::Pawn(_) = chess_move.piece() {
+            // pawn captures are identified by the file they leave
+            if chess_move.captured().is_some() {
+                res.push_str(&from_str[0..1]);
+                res.push('x');
+            }
+        } else {
+            res.push(chess_move.piece().as_char().to_ascii_uppercase());
+
+            // --- Disambiguation ---
+            // Only add as much of the starting square as is needed to tell apart the pieces of
+            // the same kind that could also move to the destination square
+            let others: Vec<Square> = self
+                .generate_legal_moves()
+                .iter()
+                .filter(|m| {
+                    m.piece() == chess_move.piece()
+                        && m.destination_square() == to
+                        && m.source_square() != from
+                })
+                .map(|m| m.source_square())
+                .collect();
+            if !others.is_empty() {
+                if others.iter().all(|s| s.file() != from.file()) {
+                    res.push_str(&from_str[0..1]);
+                } else if others.iter().all(|s| s.rank() != from.rank()) {
+                    res.push_str(&from_str[1..2]);
+                } else {
+                    res.push_str(&from_str);
+                }
+            }
+
+            if chess_move.captured().is_some() {
+                res.push('x');
+            }
+        }
+
+        res.push_str(&to.to_string());
+        if let Some(piece) = chess_move.promotion() {
+            res.push('=');
+            res.push(piece.as_char().to_ascii_uppercase());
+        }
+        res
+    }
+
+    /// Finds the legal move described by a move in Standard Algebraic Notation.
+    ///
+    /// Common deviations from strict SAN are accepted: check and mate markers may be missing,
+    /// castling may be written with zeros, the '=' of promotions may be left out, and
+    /// annotations such as "!?" as well as an "e.p." suffix are ignored.
+    pub fn parse_san(&self, s: &str) -> Result<ChessMove, MoveParseError> {
+        let malformed = || MoveParseError::Malformed(s.to_string());
+        let mut san = s.trim();
+        san = san.strip_suffix("e.p.").unwrap_or(san).trim_end();
+        san = san.trim_end_matches(['+', '#', '!', '?']);
+        if !san.is_ascii() || san.is_empty() {
+            return Err(malformed());
+        }
+
+        let candidates: Vec<ChessMove> = match san {
+            "O-O" | "0-0" => self
+                .generate_legal_moves()
+                .into_iter()
+                .filter(|m| m.castling() == Some(Side::King))
+                .collect(),
+            "O-O-O" | "0-0-0" => self
+                .generate_legal_moves()
+                .into_iter()
+                .filter(|m| m.castling() == Some(Side::Queen))
+                .collect(),
+            _ => {
+                let pattern = SanPattern::parse(san, self.side_to_move).ok_or_else(malformed)?;
+                self.generate_legal_moves()
+                    .into_iter()
+                    .filter(|m| pattern.matches(m))
+                    .collect()
+            }
+        };
+
+        match candidates.len() {
+            0 => Err(MoveParseError::Illegal(s.to_string())),
+            1 => Ok(candidates[0]),
+            _ => Err(MoveParseError::Ambiguous(s.to_string())),
+        }
+    }
+}
+
+/// The parts of a non-castling SAN move.
+struct SanPattern {
+    piece: Piece,
+    from_file: Option<File>,
+    from_rank: Option<Rank>,
+    to: Square,
+    promotion: Option<Piece>,
+}
+impl SanPattern {
+    fn parse(san: &str, colour: Colour) -> Option<Self> {
+        let mut rest = san;
+
+        let piece = match rest.chars().next()? {
+            'K' => Piece::King(colour),
+            'Q' => Piece::Queen(colour),
+            'R' => Piece::Rook(colour),
+            'B' => Piece::Bishop(colour),
+            'N' => Piece::Knight(colour),
+            _ => Piece::Pawn(colour),
+        };
+        if piece != Piece::Pawn(colour) {
+            rest = &rest[1..];
+        }
+
+        // the promotion piece may be written as "=Q", "Q" or "q"
+        let promotion = match rest.chars().last()? {
+            c @ ('Q' | 'R' | 'B' | 'N' | 'q' | 'r' | 'b' | 'n') if rest.len() > 2 => {
+                rest = rest[..rest.len() - 1].trim_end_matches('=');
+                match c.to_ascii_uppercase() {
+                    'Q' => Some(Piece::Queen(colour)),
+                    'R' => Some(Piece::Rook(colour)),
+                    'B' => Some(Piece::Bishop(colour)),
+                    _ => Some(Piece::Knight(colour)),
+                }
+            }
+            _ => None,
+        };
+
+        if rest.len() < 2 {
+            return None;
+        }
+        let to = rest[rest.len() - 2..].parse().ok()?;
+
+        // whatever is left disambiguates the starting square, optionally followed by a capture
+        // or, in long algebraic notation, a dash
+        let mut from_file = None;
+        let mut from_rank = None;
+        let prefix = rest[..rest.len() - 2].trim_end_matches(['x', ':', '-']);
+        for c in prefix.chars() {
+            match c {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => {
+                    from_file = Some(File::ALL[c as usize - 'a' as usize])
+                }
+                '1'..='8' if from_rank.is_none() => {
+                    from_rank = Some(Rank::ALL[c as usize - '1' as usize])
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            piece,
+            from_file,
+            from_rank,
+            to,
+            promotion,
+        })
+    }
+
+    fn matches(&self, chess_move: &ChessMove) -> bool {
+        discriminant(&chess_move.piece()) == discriminant(&self.piece)
+            && chess_move.destination_square() == self.to
+            && chess_move.promotion() == self.promotion
+            && chess_move.castling().is_none()
+            && self
+                .from_file
+                .is_none_or(|f| chess_move.source_square().file() == f)
+            && self
+                .from_rank
+                .is_none_or(|r| chess_move.source_square().rank() == r)
+    }
+}
@@ -88,7 +88,7 @@ pub fn from_str(s: &str) -> i32 {
 }
 
 pub fn to_str(n: i32) -> Option<String> {
-    if n >= 0 && n < 64 {
+    if (0..64).contains(&n) {
         let mut res = String::from(match n % 8 {
             0 => 'a',
             1 => 'b',
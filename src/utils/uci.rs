@@ -0,0 +1,68 @@
+use super::Piece;
+use crate::{
+    board::{Board, ChessMove},
+    Square,
+};
+use std::{error::Error, fmt::Display};
+
+/// Reasons a textual move can be rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveParseError {
+    /// The input is not a move in the expected notation.
+    Malformed(String),
+    /// The input is a well-formed move, but it cannot be played in the position.
+    Illegal(String),
+    /// The input fits more than one legal move.
+    Ambiguous(String),
+}
+impl Display for MoveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(s) => write!(f, "malformed move '{s}'"),
+            Self::Illegal(s) => write!(f, "illegal move '{s}'"),
+            Self::Ambiguous(s) => write!(f, "ambiguous move '{s}'"),
+        }
+    }
+}
+impl Error for MoveParseError {}
+
+/// Writes the move in UCI long algebraic notation, e.g. "e2e4", "e7e8q" or "e1g1".
+impl Display for ChessMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.source_square(), self.destination_square())?;
+        if let Some(piece) = self.promotion() {
+            write!(f, "{}", piece.as_char().to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+impl Board {
+    /// Finds the legal move described by a move in UCI long algebraic notation.
+    pub fn parse_uci_move(&self, s: &str) -> Result<ChessMove, MoveParseError> {
+        let malformed = || MoveParseError::Malformed(s.to_string());
+        if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+            return Err(malformed());
+        }
+        let from: Square = s[0..2].parse().map_err(|_| malformed())?;
+        let to: Square = s[2..4].parse().map_err(|_| malformed())?;
+        let colour = self.side_to_move;
+        let promotion = match s[4..].chars().next() {
+            None => None,
+            Some('q') => Some(Piece::Queen(colour)),
+            Some('r') => Some(Piece::Rook(colour)),
+            Some('b') => Some(Piece::Bishop(colour)),
+            Some('n') => Some(Piece::Knight(colour)),
+            Some(_) => return Err(malformed()),
+        };
+
+        self.generate_legal_moves()
+            .into_iter()
+            .find(|m| {
+                m.source_square() == from
+                    && m.destination_square() == to
+                    && m.promotion() == promotion
+            })
+            .ok_or_else(|| MoveParseError::Illegal(s.to_string()))
+    }
+}
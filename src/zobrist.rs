@@ -0,0 +1,148 @@
+use crate::{
+    board::Board,
+    piece_map_bitboards,
+    utils::{Colour, Piece},
+};
+
+/// Random keys XORed together to make up the hash key of a position.
+struct Keys {
+    /// One key per square for each piece, white's pieces first, in the order of `piece_boards`.
+    pieces: [[u64; 64]; 12],
+    /// White kingside, white queenside, black kingside, black queenside.
+    castling: [u64; 4],
+    /// Included when black is to move.
+    black_to_move: u64,
+    /// One key per file of the en passant square.
+    en_passant: [u64; 8],
+}
+
+/// SplitMix64, so that the keys can be generated at compile time and are the same on every run.
+const fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+const KEYS: Keys = {
+    let mut state = 0;
+    let mut keys = Keys {
+        pieces: [[0; 64]; 12],
+        castling: [0; 4],
+        black_to_move: 0,
+        en_passant: [0; 8],
+    };
+    let mut piece = 0;
+    while piece < 12 {
+        let mut square = 0;
+        while square < 64 {
+            keys.pieces[piece][square] = split_mix(&mut state);
+            square += 1;
+        }
+        piece += 1;
+    }
+    let mut i = 0;
+    while i < 4 {
+        keys.castling[i] = split_mix(&mut state);
+        i += 1;
+    }
+    keys.black_to_move = split_mix(&mut state);
+    let mut file = 0;
+    while file < 8 {
+        keys.en_passant[file] = split_mix(&mut state);
+        file += 1;
+    }
+    keys
+};
+
+fn piece_boards(side: &piece_map_bitboards) -> [u64; 6] {
+    [
+        side.pawns,
+        side.knights,
+        side.bishops,
+        side.rooks,
+        side.queens,
+        side.king,
+    ]
+}
+
+/// XORs together the keys of the squares set in `bitboard`.
+fn squares_key(keys: &[u64; 64], mut bitboard: u64) -> u64 {
+    let mut res = 0;
+    while bitboard != 0 {
+        res ^= keys[bitboard.trailing_zeros() as usize];
+        bitboard &= bitboard - 1;
+    }
+    res
+}
+
+/// The key of a single piece standing on a square.
+pub(crate) fn piece_key(piece: Piece, square: i32) -> u64 {
+    let (index, colour) = match piece {
+        Piece::Pawn(c) => (0, c),
+        Piece::Knight(c) => (1, c),
+        Piece::Bishop(c) => (2, c),
+        Piece::Rook(c) => (3, c),
+        Piece::Queen(c) => (4, c),
+        Piece::King(c) => (5, c),
+    };
+    let index = match colour {
+        Colour::White => index,
+        Colour::Black => index + 6,
+    };
+    KEYS.pieces[index][square as usize]
+}
+
+/// The keys of everything but the pieces, which change together in only a few ways.
+pub(crate) fn state_key(board: &Board) -> u64 {
+    let rights = board.castling_rights;
+    let mut res = 0;
+    for (i, right) in [
+        rights.white_king,
+        rights.white_queen,
+        rights.black_king,
+        rights.black_queen,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if right {
+            res ^= KEYS.castling[i];
+        }
+    }
+    if board.side_to_move == Colour::Black {
+        res ^= KEYS.black_to_move;
+    }
+    if board.killer_square >= 0 {
+        res ^= KEYS.en_passant[board.killer_square as usize % 8];
+    }
+    res
+}
+
+/// Computes the hash key of a board from scratch.
+pub(crate) fn full_key(board: &Board) -> u64 {
+    let white = piece_boards(&board.base.white);
+    let black = piece_boards(&board.base.black);
+    let mut res = state_key(board);
+    for (keys, bitboard) in KEYS.pieces.iter().zip(white.into_iter().chain(black)) {
+        res ^= squares_key(keys, bitboard);
+    }
+    res
+}
+
+/// Derives the hash key of `new` from the key of `old` by only hashing what changed between them.
+pub(crate) fn updated_key(old: &Board, new: &Board) -> u64 {
+    let old_boards = piece_boards(&old.base.white)
+        .into_iter()
+        .chain(piece_boards(&old.base.black));
+    let new_boards = piece_boards(&new.base.white)
+        .into_iter()
+        .chain(piece_boards(&new.base.black));
+
+    let mut res = old.zobrist ^ state_key(old) ^ state_key(new);
+    for (keys, (old_bitboard, new_bitboard)) in KEYS.pieces.iter().zip(old_boards.zip(new_boards)) {
+        res ^= squares_key(keys, old_bitboard ^ new_bitboard);
+    }
+    res
+}
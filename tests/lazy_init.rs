@@ -0,0 +1,15 @@
+//! Runs in its own process, so that nothing has filled the attack tables before the boards
+//! below are created.
+
+use chess_backend::Board;
+
+#[test]
+fn test_perft_without_init() {
+    // boards fill the attack tables themselves, whichever thread creates them first
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| Board::default().perft(3).nodes))
+        .collect();
+    for handle in handles {
+        assert_eq!(8902, handle.join().unwrap());
+    }
+}
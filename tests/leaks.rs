@@ -0,0 +1,98 @@
+//! Counts the blocks the process gets from `malloc` and gives back to `free`, so that leaks in
+//! the C library show up. The test runs without the test harness, on the main thread only, so
+//! nothing else allocates while it counts.
+
+use chess_backend::{Board, CMK_POSITION, KILLER_POSITION, START_POSITION, TRICKY_POSITION};
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod counting {
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicIsize, Ordering};
+
+    /// The blocks allocated and not yet freed since the process started.
+    pub static LIVE_BLOCKS: AtomicIsize = AtomicIsize::new(0);
+
+    // glibc's own entry points, so that forwarding to them doesn't come back here
+    extern "C" {
+        fn __libc_malloc(size: usize) -> *mut c_void;
+        fn __libc_calloc(count: usize, size: usize) -> *mut c_void;
+        fn __libc_realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+        fn __libc_free(ptr: *mut c_void);
+    }
+
+    fn count(ptr: *mut c_void, blocks: isize) -> *mut c_void {
+        if !ptr.is_null() {
+            LIVE_BLOCKS.fetch_add(blocks, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn malloc(size: usize) -> *mut c_void {
+        count(__libc_malloc(size), 1)
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn calloc(count_: usize, size: usize) -> *mut c_void {
+        count(__libc_calloc(count_, size), 1)
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+        let res = __libc_realloc(ptr, size);
+        match (ptr.is_null(), res.is_null()) {
+            (true, _) => count(res, 1),
+            // a zero size frees the block
+            (false, true) if size == 0 => {
+                count(ptr, -1);
+                res
+            }
+            _ => res,
+        }
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn free(ptr: *mut c_void) {
+        count(ptr, -1);
+        __libc_free(ptr)
+    }
+}
+
+/// Checks every legal move `depth` plies deep, which checks the attacks on the king once for
+/// every pseudo-legal move.
+fn check_moves(board: &Board, depth: u32) -> u64 {
+    let moves = board.generate_legal_moves();
+    if depth <= 1 {
+        return moves.iter().filter(|m| m.board.is_check()).count() as u64 + 1;
+    }
+    moves.iter().map(|m| check_moves(&m.board, depth - 1)).sum()
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn main() {
+    use std::sync::atomic::Ordering;
+
+    chess_backend::init();
+    let boards = [
+        START_POSITION,
+        TRICKY_POSITION,
+        KILLER_POSITION,
+        CMK_POSITION,
+    ]
+    .map(|fen| Board::try_from(fen).unwrap());
+
+    // the first moves fill tables that live as long as the process
+    check_moves(&boards[0], 1);
+    let before = counting::LIVE_BLOCKS.load(Ordering::Relaxed);
+    for board in &boards {
+        check_moves(board, 2);
+    }
+    let after = counting::LIVE_BLOCKS.load(Ordering::Relaxed);
+    assert_eq!(before, after, "{} blocks leaked", after - before);
+    println!("test_legality_checks_do_not_leak ... ok");
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+fn main() {
+    println!("test_legality_checks_do_not_leak ... ignored, needs glibc to count allocations");
+}